@@ -1,2 +1,43 @@
 lang-name = English
 hello-world = Hello
+broken-version-unreadable = Unable to read the version file: { $message }
+broken-version-invalid-json = The version file is not valid JSON (line { $line }, column { $column })
+broken-version-id-mismatch = Version id "{ $found }" does not match its folder name "{ $expected }"
+broken-version-missing-parent = Requires version "{ $parent }", which is not installed
+broken-version-circular-inheritance = Version inheritance forms a loop: { $chain }
+broken-version-missing-jar = The game jar is missing: { $path }
+broken-version-incomplete-manifest = The version file is missing required fields: { $fields }
+repair-action-reinstall = Reinstall this version
+repair-action-redownload-jar = Download the game jar again
+repair-action-install-parent = Install the required version
+repair-action-fix-version-id = Rename the version to match its folder
+crash-cause-java-version-mismatch = The game requires Java { $required }, but Java { $current } was used
+crash-cause-missing-mod-dependency = { $detail ->
+    [none] A mod is missing a required dependency
+   *[other] A mod requires "{ $detail }", which is not installed
+}
+crash-cause-mixin-failure = { $detail ->
+    [none] A mod failed to apply its mixins
+   *[other] A mod failed to apply its mixins: { $detail }
+}
+crash-cause-open-gl-driver = The graphics driver failed to provide OpenGL, try updating the graphics driver
+crash-cause-out-of-memory = { $memory ->
+    [none] The game ran out of memory, try allocating more memory
+   *[other] The game ran out of memory with { $memory } MB allocated, try allocating more memory
+}
+crash-cause-duplicate-mods = { $detail ->
+    [none] The same mod is installed more than once
+   *[other] The mod "{ $detail }" is installed more than once
+}
+crash-cause-invalid-jvm-arguments = { $detail ->
+    [none] Java rejected the JVM arguments
+   *[other] Java rejected the JVM argument: { $detail }
+}
+crash-cause-jvm-crash = { $path ->
+    [none] The Java Virtual Machine crashed
+   *[other] The Java Virtual Machine crashed, see { $path }
+}
+crash-cause-unknown = { $code ->
+    [none] The game exited unexpectedly
+   *[other] The game exited unexpectedly with code { $code }
+}
@@ -1,2 +1,43 @@
 lang-name = 简体中文
 hello-world = 你好
+broken-version-unreadable = 无法读取版本文件：{ $message }
+broken-version-invalid-json = 版本文件不是有效的 JSON（第 { $line } 行，第 { $column } 列）
+broken-version-id-mismatch = 版本 ID「{ $found }」与文件夹名称「{ $expected }」不一致
+broken-version-missing-parent = 需要版本「{ $parent }」，但该版本尚未安装
+broken-version-circular-inheritance = 版本继承关系出现循环：{ $chain }
+broken-version-missing-jar = 游戏 jar 文件缺失：{ $path }
+broken-version-incomplete-manifest = 版本文件缺少必要字段：{ $fields }
+repair-action-reinstall = 重新安装此版本
+repair-action-redownload-jar = 重新下载游戏 jar 文件
+repair-action-install-parent = 安装所需的版本
+repair-action-fix-version-id = 将版本重命名为与文件夹一致
+crash-cause-java-version-mismatch = 游戏需要 Java { $required }，但使用的是 Java { $current }
+crash-cause-missing-mod-dependency = { $detail ->
+    [none] 有模组缺少必需的前置
+   *[other] 有模组需要「{ $detail }」，但它尚未安装
+}
+crash-cause-mixin-failure = { $detail ->
+    [none] 有模组的 Mixin 注入失败
+   *[other] 有模组的 Mixin 注入失败：{ $detail }
+}
+crash-cause-open-gl-driver = 显卡驱动无法提供 OpenGL，请尝试更新显卡驱动
+crash-cause-out-of-memory = { $memory ->
+    [none] 游戏内存不足，请尝试分配更多内存
+   *[other] 游戏内存不足（已分配 { $memory } MB），请尝试分配更多内存
+}
+crash-cause-duplicate-mods = { $detail ->
+    [none] 同一个模组被重复安装
+   *[other] 模组「{ $detail }」被重复安装
+}
+crash-cause-invalid-jvm-arguments = { $detail ->
+    [none] Java 拒绝了 JVM 参数
+   *[other] Java 拒绝了 JVM 参数：{ $detail }
+}
+crash-cause-jvm-crash = { $path ->
+    [none] Java 虚拟机崩溃
+   *[other] Java 虚拟机崩溃，详见 { $path }
+}
+crash-cause-unknown = { $code ->
+    [none] 游戏意外退出
+   *[other] 游戏意外退出，退出代码 { $code }
+}
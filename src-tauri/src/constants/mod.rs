@@ -12,12 +12,41 @@ pub mod launcher {
 pub mod minecraft_dir {
     pub const VERSIONS_DIR_NAME: &str = "versions";
     pub const ASSETS_DIR_NAME: &str = "assets";
+    pub const ASSET_INDEXES_DIR_NAME: &str = "indexes";
+    pub const ASSET_OBJECTS_DIR_NAME: &str = "objects";
+    pub const VIRTUAL_ASSETS_DIR_NAME: &str = "virtual";
+    pub const RESOURCES_DIR_NAME: &str = "resources";
+    pub const LOG_CONFIGS_DIR_NAME: &str = "log_configs";
     pub const NATIVES_DIR_NAME: &str = "natives";
     pub const LIBRARIES_DIR_NAME: &str = "libraries";
+    pub const MODS_DIR_NAME: &str = "mods";
 }
 
 pub mod file_system {
     pub const LAUNCHER_DIR_NAME: &str = ".kcl";
     pub const SETTINGS_FILE_NAME: &str = "settings.json";
     pub const LOG_DIR_NAME: &str = "logs";
+    pub const CACHE_DIR_NAME: &str = "cache";
+    pub const RUNTIME_DIR_NAME: &str = "runtime";
+}
+
+pub mod network {
+    pub const OFFICIAL_META_BASE_URL: &str = "https://piston-meta.mojang.com";
+    pub const VERSION_MANIFEST_PATH: &str = "mc/game/version_manifest_v2.json";
+    pub const JAVA_RUNTIME_INDEX_PATH: &str =
+        "v1/products/java-runtime/2ec0cc96c44e5a76b9c8b7c39df7210883d12871/all.json";
+    pub const OFFICIAL_ASSETS_BASE_URL: &str = "https://resources.download.minecraft.net";
+    pub const MOJANG_LIBRARIES_URL: &str = "https://libraries.minecraft.net/";
+    // where a mirror serves each mojang host, relative to the meta base url
+    pub const MOJANG_MIRROR_PATHS: &[(&str, &str)] = &[
+        ("piston-meta.mojang.com", ""),
+        ("launchermeta.mojang.com", ""),
+        ("piston-data.mojang.com", "piston-data"),
+        ("launcher.mojang.com", "launcher"),
+    ];
+    pub const ADOPTIUM_API_URL: &str = "https://api.adoptium.net";
+    pub const ZULU_API_URL: &str = "https://api.azul.com";
+    pub const LIBERICA_API_URL: &str = "https://api.bell-sw.com";
+    pub const FABRIC_META_URL: &str = "https://meta.fabricmc.net";
+    pub const QUILT_META_URL: &str = "https://meta.quiltmc.org";
 }
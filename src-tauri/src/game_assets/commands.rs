@@ -0,0 +1,68 @@
+use crate::constants::minecraft_dir::ASSETS_DIR_NAME;
+use crate::game_assets::downloader::{
+    collect_asset_objects, download_asset_index, download_assets_task, get_asset_index_path,
+    read_asset_index, verify_assets_task,
+};
+use crate::game_assets::layout::materialize_asset_layout;
+use crate::game_assets::models::{AssetIssue, AssetIssueKind, AssetLayout};
+use crate::game_installer::catalog::VersionCatalogClient;
+use crate::game_resolver::resolve::resolve_installed_version;
+use crate::scheduler::Scheduler;
+use crate::settings::modules::download::DownloadSettings;
+use crate::settings::store::SettingsStore;
+use crate::utils::abs_path_buf::AbsPathBuf;
+use crate::utils::command::CommandResult;
+use anyhow::Context;
+use macros::command;
+use reqwest::Client;
+use std::sync::Arc;
+use tauri::State;
+
+#[command]
+pub async fn install_game_assets(
+    catalog: State<'_, Arc<VersionCatalogClient>>,
+    scheduler: State<'_, Scheduler>,
+    store: State<'_, Arc<SettingsStore>>,
+    minecraft_dir: String,
+    version_id: String,
+    verify_only: bool,
+) -> CommandResult<Vec<AssetIssue>> {
+    let minecraft_dir = AbsPathBuf::new(&minecraft_dir)?;
+    let manifest = resolve_installed_version(minecraft_dir.clone(), &version_id).await?;
+    let asset_index = manifest
+        .asset_index
+        .context("Version does not declare an asset index")?;
+    let assets_dir = minecraft_dir.join(ASSETS_DIR_NAME);
+    let settings = store.get::<DownloadSettings>();
+
+    let index = if verify_only {
+        match read_asset_index(&asset_index, &assets_dir).await {
+            Ok(index) => index,
+            Err(_) => {
+                let path = get_asset_index_path(&assets_dir, &asset_index.id);
+                return Ok(vec![AssetIssue {
+                    name: format!("{}.json", asset_index.id),
+                    path: path.to_string_lossy().to_string(),
+                    kind: AssetIssueKind::Missing,
+                }]);
+            }
+        }
+    } else {
+        let url = catalog.resolve_url(&asset_index.url);
+        download_asset_index(&Client::new(), &url, &asset_index, &assets_dir).await?
+    };
+
+    let objects = collect_asset_objects(&index, &assets_dir, &settings.assets_base_url);
+    if verify_only {
+        return Ok(scheduler.run(verify_assets_task(objects)).await?);
+    }
+    scheduler
+        .run(download_assets_task(Client::new(), objects))
+        .await?;
+
+    // resources of pre-1.6 versions live in the game dir, they are copied when launching
+    if index.layout() == AssetLayout::Virtual {
+        materialize_asset_layout(&index, &assets_dir, &asset_index.id, &minecraft_dir).await?;
+    }
+    Ok(vec![])
+}
@@ -0,0 +1,169 @@
+use crate::constants::minecraft_dir::{ASSET_INDEXES_DIR_NAME, ASSET_OBJECTS_DIR_NAME};
+use crate::game_assets::models::{AssetIndexContent, AssetIssue, AssetIssueKind};
+use crate::game_installer::download::{download_file, file_sha1, is_file_intact};
+use crate::game_resolver::models::AssetIndex;
+use crate::scheduler::{Task, parallel, pipeline, task};
+use crate::utils::abs_path_buf::AbsPathBuf;
+use anyhow::{Context, Result};
+use reqwest::Client;
+use std::collections::HashSet;
+use tokio::fs;
+
+const OBJECTS_PER_TASK: usize = 64;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AssetObjectDownload {
+    pub name: String,
+    pub url: String,
+    pub path: AbsPathBuf,
+    pub hash: String,
+    pub size: u64,
+}
+
+pub fn get_asset_index_path(assets_dir: &AbsPathBuf, index_id: &str) -> AbsPathBuf {
+    assets_dir
+        .join(ASSET_INDEXES_DIR_NAME)
+        .join(format!("{index_id}.json"))
+}
+
+pub async fn download_asset_index(
+    client: &Client,
+    url: &str,
+    asset_index: &AssetIndex,
+    assets_dir: &AbsPathBuf,
+) -> Result<AssetIndexContent> {
+    let path = get_asset_index_path(assets_dir, &asset_index.id);
+    let sha1 = Some(asset_index.sha1.as_str());
+    if !is_file_intact(&path, sha1, Some(asset_index.size)).await {
+        download_file(client, url, &path, sha1, Some(asset_index.size)).await?;
+    }
+    read_asset_index(asset_index, assets_dir).await
+}
+
+pub async fn read_asset_index(
+    asset_index: &AssetIndex,
+    assets_dir: &AbsPathBuf,
+) -> Result<AssetIndexContent> {
+    let path = get_asset_index_path(assets_dir, &asset_index.id);
+    let content = fs::read(&path)
+        .await
+        .with_context(|| format!("Failed to read asset index {}", path.display()))?;
+    serde_json::from_slice(&content)
+        .with_context(|| format!("Invalid asset index {}", asset_index.id))
+}
+
+pub fn collect_asset_objects(
+    index: &AssetIndexContent,
+    assets_dir: &AbsPathBuf,
+    objects_base_url: &str,
+) -> Vec<AssetObjectDownload> {
+    let objects_dir = assets_dir.join(ASSET_OBJECTS_DIR_NAME);
+    let base_url = objects_base_url.trim_end_matches('/');
+    let mut seen_hashes = HashSet::new();
+
+    let mut objects = index.objects.iter().collect::<Vec<_>>();
+    objects.sort_by_key(|(name, _)| *name);
+    objects
+        .into_iter()
+        .filter(|(_, object)| seen_hashes.insert(object.hash.clone()))
+        .map(|(name, object)| AssetObjectDownload {
+            name: name.clone(),
+            url: format!("{base_url}/{}", object.relative_path()),
+            path: objects_dir.join(object.relative_path()),
+            hash: object.hash.clone(),
+            size: object.size,
+        })
+        .collect()
+}
+
+fn batch_weight(batch: &[AssetObjectDownload]) -> u64 {
+    (batch.iter().map(|o| o.size).sum::<u64>() / 1024).max(1)
+}
+
+pub fn download_assets_task(
+    client: Client,
+    objects: Vec<AssetObjectDownload>,
+) -> impl Task<Input = (), Output = Vec<()>> {
+    let batch_count = objects.len().div_ceil(OBJECTS_PER_TASK);
+    let tasks = objects
+        .chunks(OBJECTS_PER_TASK)
+        .map(<[_]>::to_vec)
+        .enumerate()
+        .map(move |(i, batch)| {
+            let client = client.clone();
+            let weight = batch_weight(&batch);
+            let name = format!("Download assets ({}/{batch_count})", i + 1);
+            task(name, move |_: ()| {
+                download_batch(client.clone(), batch.clone())
+            })
+            .with_weight(weight)
+        })
+        .collect::<Vec<_>>();
+    parallel("Download assets").extend(tasks).build()
+}
+
+async fn download_batch(client: Client, batch: Vec<AssetObjectDownload>) -> Result<()> {
+    for object in batch {
+        let (sha1, size) = (Some(object.hash.as_str()), Some(object.size));
+        if is_file_intact(&object.path, sha1, size).await {
+            continue;
+        }
+        download_file(&client, &object.url, &object.path, sha1, size)
+            .await
+            .with_context(|| format!("Failed to download asset {}", object.name))?;
+    }
+    Ok(())
+}
+
+pub fn verify_assets_task(
+    objects: Vec<AssetObjectDownload>,
+) -> impl Task<Input = (), Output = Vec<AssetIssue>> {
+    let batch_count = objects.len().div_ceil(OBJECTS_PER_TASK);
+    let tasks = objects
+        .chunks(OBJECTS_PER_TASK)
+        .map(<[_]>::to_vec)
+        .enumerate()
+        .map(move |(i, batch)| {
+            let weight = batch_weight(&batch);
+            let name = format!("Verify assets ({}/{batch_count})", i + 1);
+            task(name, move |_: ()| verify_batch(batch.clone())).with_weight(weight)
+        })
+        .collect::<Vec<_>>();
+
+    pipeline("Verify assets")
+        .first(parallel("Check asset objects").extend(tasks).build())
+        .then(
+            task("Collect asset issues", |issues: Vec<Vec<AssetIssue>>| async move {
+                Ok(issues.into_iter().flatten().collect())
+            })
+            .hidden_in_view(),
+        )
+        .build()
+}
+
+async fn verify_batch(batch: Vec<AssetObjectDownload>) -> Result<Vec<AssetIssue>> {
+    let mut issues = Vec::new();
+    for object in batch {
+        if let Some(kind) = check_object(&object).await {
+            issues.push(AssetIssue {
+                name: object.name,
+                path: object.path.to_string_lossy().to_string(),
+                kind,
+            });
+        }
+    }
+    Ok(issues)
+}
+
+async fn check_object(object: &AssetObjectDownload) -> Option<AssetIssueKind> {
+    let Ok(metadata) = fs::metadata(&object.path).await else {
+        return Some(AssetIssueKind::Missing);
+    };
+    if metadata.len() != object.size {
+        return Some(AssetIssueKind::Corrupt);
+    }
+    match file_sha1(&object.path).await {
+        Ok(actual) if actual.eq_ignore_ascii_case(&object.hash) => None,
+        _ => Some(AssetIssueKind::Corrupt),
+    }
+}
@@ -0,0 +1,69 @@
+use crate::constants::minecraft_dir::{
+    ASSET_OBJECTS_DIR_NAME, RESOURCES_DIR_NAME, VIRTUAL_ASSETS_DIR_NAME,
+};
+use crate::game_assets::models::{AssetIndexContent, AssetLayout};
+use crate::game_installer::download::is_file_intact;
+use crate::utils::abs_path_buf::AbsPathBuf;
+use anyhow::{Context, Result, bail};
+use log::info;
+use std::path::{Component, Path};
+use tokio::fs;
+
+pub fn get_game_assets_dir(
+    layout: AssetLayout,
+    assets_dir: &AbsPathBuf,
+    index_id: &str,
+    game_dir: &AbsPathBuf,
+) -> AbsPathBuf {
+    match layout {
+        AssetLayout::Modern => assets_dir.clone(),
+        AssetLayout::Virtual => assets_dir.join(VIRTUAL_ASSETS_DIR_NAME).join(index_id),
+        AssetLayout::MapToResources => game_dir.join(RESOURCES_DIR_NAME),
+    }
+}
+
+// copies objects to where old versions look for them, returns the directory for ${game_assets}
+pub async fn materialize_asset_layout(
+    index: &AssetIndexContent,
+    assets_dir: &AbsPathBuf,
+    index_id: &str,
+    game_dir: &AbsPathBuf,
+) -> Result<AbsPathBuf> {
+    let layout = index.layout();
+    let target_dir = get_game_assets_dir(layout, assets_dir, index_id, game_dir);
+    if layout == AssetLayout::Modern {
+        return Ok(target_dir);
+    }
+
+    info!(
+        "Materializing {layout:?} assets into {}",
+        target_dir.display()
+    );
+    let objects_dir = assets_dir.join(ASSET_OBJECTS_DIR_NAME);
+    for (name, object) in &index.objects {
+        // the index may come from a mirror, its names must stay inside the target directory
+        let relative_path = Path::new(name);
+        let is_safe = relative_path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+        if !is_safe || relative_path.as_os_str().is_empty() {
+            bail!(
+                "Asset name {name} points outside of {}",
+                target_dir.display()
+            );
+        }
+        let target = target_dir.join(relative_path);
+        if is_file_intact(&target, Some(&object.hash), Some(object.size)).await {
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let source = objects_dir.join(object.relative_path());
+        fs::copy(&source, &target)
+            .await
+            .with_context(|| format!("Failed to copy asset {name} from {}", source.display()))?;
+    }
+
+    Ok(target_dir)
+}
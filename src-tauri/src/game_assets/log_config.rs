@@ -0,0 +1,65 @@
+use crate::constants::minecraft_dir::LOG_CONFIGS_DIR_NAME;
+use crate::game_installer::catalog::VersionCatalogClient;
+use crate::game_installer::download::{download_file, is_file_intact};
+use crate::game_resolver::models::LoggingConfig;
+use crate::scheduler::{Task, task};
+use crate::utils::abs_path_buf::AbsPathBuf;
+use anyhow::{Context, Result};
+use reqwest::Client;
+use std::path::Path;
+use std::sync::Arc;
+
+const DEFAULT_LOGGING_ARGUMENT: &str = "-Dlog4j.configurationFile=${path}";
+
+pub fn get_log_config_path(assets_dir: &AbsPathBuf, config: &LoggingConfig) -> AbsPathBuf {
+    let file_name = config.file.id.clone().unwrap_or_else(|| {
+        let url = config.file.url.trim_end_matches('/');
+        url.rsplit('/').next().unwrap_or(url).to_string()
+    });
+    assets_dir.join(LOG_CONFIGS_DIR_NAME).join(file_name)
+}
+
+/// The JVM argument pointing log4j at `path`, a custom config falls back to the standard property.
+pub fn get_logging_argument(config: Option<&LoggingConfig>, path: &Path) -> String {
+    config
+        .map_or(DEFAULT_LOGGING_ARGUMENT, |c| c.argument.as_str())
+        .replace("${path}", &path.to_string_lossy())
+}
+
+pub async fn download_log_config(
+    client: &Client,
+    url: &str,
+    config: &LoggingConfig,
+    assets_dir: &AbsPathBuf,
+) -> Result<AbsPathBuf> {
+    let path = get_log_config_path(assets_dir, config);
+    let sha1 = Some(config.file.sha1.as_str()).filter(|sha1| !sha1.is_empty());
+    let size = Some(config.file.size).filter(|size| *size > 0);
+    if !is_file_intact(&path, sha1, size).await {
+        download_file(client, url, &path, sha1, size)
+            .await
+            .context("Failed to download log config")?;
+    }
+    Ok(path)
+}
+
+pub fn download_log_config_task(
+    client: Client,
+    catalog: Arc<VersionCatalogClient>,
+    config: Option<LoggingConfig>,
+    assets_dir: AbsPathBuf,
+) -> impl Task<Input = (), Output = ()> {
+    task("Download log config", move |_: ()| {
+        let client = client.clone();
+        let catalog = catalog.clone();
+        let config = config.clone();
+        let assets_dir = assets_dir.clone();
+        async move {
+            if let Some(config) = config {
+                let url = catalog.resolve_url(&config.file.url);
+                download_log_config(&client, &url, &config, &assets_dir).await?;
+            }
+            Ok(())
+        }
+    })
+}
@@ -1 +1,6 @@
-
+pub mod commands;
+pub mod downloader;
+pub mod layout;
+pub mod log_config;
+pub mod models;
+mod tests;
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetIndexContent {
+    pub objects: HashMap<String, AssetObject>,
+    #[serde(default, rename = "virtual")]
+    pub is_virtual: bool,
+    #[serde(default, rename = "map_to_resources")]
+    pub map_to_resources: bool,
+}
+
+impl AssetIndexContent {
+    pub fn layout(&self) -> AssetLayout {
+        if self.map_to_resources {
+            AssetLayout::MapToResources
+        } else if self.is_virtual {
+            AssetLayout::Virtual
+        } else {
+            AssetLayout::Modern
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum AssetLayout {
+    #[default]
+    Modern,
+    Virtual,        // 1.6 ~ 1.7.2, files copied to assets/virtual/<index id>
+    MapToResources, // pre-1.6, files copied to <game dir>/resources
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetObject {
+    pub hash: String,
+    pub size: u64,
+}
+
+impl AssetObject {
+    // objects are stored as <first two chars of hash>/<hash>
+    pub fn relative_path(&self) -> String {
+        let prefix = self.hash.get(..2).unwrap_or(&self.hash);
+        format!("{prefix}/{}", self.hash)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetIssue {
+    pub name: String,
+    pub path: String,
+    pub kind: AssetIssueKind,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum AssetIssueKind {
+    Missing,
+    Corrupt,
+}
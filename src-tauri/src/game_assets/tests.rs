@@ -0,0 +1,374 @@
+#![cfg_attr(coverage_nightly, coverage(off))]
+#![cfg(test)]
+
+use crate::constants::minecraft_dir::ASSET_OBJECTS_DIR_NAME;
+use crate::game_assets::downloader::{
+    collect_asset_objects, download_asset_index, download_assets_task, get_asset_index_path,
+    verify_assets_task,
+};
+use crate::game_assets::layout::{get_game_assets_dir, materialize_asset_layout};
+use crate::game_assets::log_config::{
+    download_log_config, download_log_config_task, get_log_config_path, get_logging_argument,
+};
+use crate::game_assets::models::{AssetIndexContent, AssetIssueKind, AssetLayout};
+use crate::game_installer::catalog::VersionCatalogClient;
+use crate::game_resolver::models::{AssetIndex, LoggingConfig};
+use crate::scheduler::Scheduler;
+use crate::utils::abs_path_buf::AbsPathBuf;
+use crate::utils::test_server::TestServer;
+use reqwest::Client;
+use serde_json::json;
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::fs;
+
+const SOUND: &[u8] = b"fake ogg";
+const LANG: &[u8] = b"{\"language.name\": \"English\"}";
+
+fn sha1_hex(data: &[u8]) -> String {
+    hex::encode(Sha1::digest(data))
+}
+
+fn object_path(hash: &str) -> String {
+    format!("/{}/{hash}", &hash[..2])
+}
+
+fn index_json() -> Vec<u8> {
+    json!({
+        "objects": {
+            "minecraft/sounds/ambient/cave/cave1.ogg": { "hash": sha1_hex(SOUND), "size": SOUND.len() },
+            "minecraft/sounds/ambient/cave/cave1_copy.ogg": { "hash": sha1_hex(SOUND), "size": SOUND.len() },
+            "minecraft/lang/en_us.json": { "hash": sha1_hex(LANG), "size": LANG.len() }
+        }
+    })
+    .to_string()
+    .into_bytes()
+}
+
+struct TestEnvironment {
+    _root: TempDir,
+    server: TestServer,
+    assets_dir: AbsPathBuf,
+    asset_index: AssetIndex,
+}
+
+impl TestEnvironment {
+    async fn new() -> Self {
+        let index = index_json();
+        let server = TestServer::start(HashMap::from([
+            ("/indexes/17.json".to_string(), index.clone()),
+            (object_path(&sha1_hex(SOUND)), SOUND.to_vec()),
+            (object_path(&sha1_hex(LANG)), LANG.to_vec()),
+        ]))
+        .await;
+        let root = TempDir::new().unwrap();
+        let assets_dir = AbsPathBuf::new(root.path()).unwrap();
+        let asset_index = AssetIndex {
+            id: "17".to_string(),
+            sha1: sha1_hex(&index),
+            size: index.len() as u64,
+            url: format!("{}/indexes/17.json", server.base_url),
+            total_size: (SOUND.len() + LANG.len()) as u64,
+        };
+        Self {
+            _root: root,
+            server,
+            assets_dir,
+            asset_index,
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_download_index_and_objects() {
+    let env = TestEnvironment::new().await;
+    let scheduler = Scheduler::new(8);
+
+    let index = download_asset_index(
+        &Client::new(),
+        &env.asset_index.url,
+        &env.asset_index,
+        &env.assets_dir,
+    )
+    .await
+    .unwrap();
+    let objects = collect_asset_objects(&index, &env.assets_dir, &env.server.base_url);
+    scheduler
+        .run(download_assets_task(Client::new(), objects.clone()))
+        .await
+        .unwrap();
+
+    assert!(get_asset_index_path(&env.assets_dir, "17").is_file());
+    assert_eq!(
+        objects.len(),
+        2,
+        "Objects with the same hash are stored once"
+    );
+    let sound_path = env
+        .assets_dir
+        .join(ASSET_OBJECTS_DIR_NAME)
+        .join(&object_path(&sha1_hex(SOUND))[1..]);
+    assert_eq!(fs::read(sound_path).await.unwrap(), SOUND);
+    let issues = scheduler.run(verify_assets_task(objects)).await.unwrap();
+    assert!(issues.is_empty(), "Unexpected issues: {issues:?}");
+}
+
+#[tokio::test]
+async fn test_verify_only_reports_missing_and_corrupt_objects() {
+    let env = TestEnvironment::new().await;
+    let scheduler = Scheduler::new(8);
+    let index = download_asset_index(
+        &Client::new(),
+        &env.asset_index.url,
+        &env.asset_index,
+        &env.assets_dir,
+    )
+    .await
+    .unwrap();
+    let objects = collect_asset_objects(&index, &env.assets_dir, &env.server.base_url);
+    let lang = objects
+        .iter()
+        .find(|o| o.name == "minecraft/lang/en_us.json")
+        .unwrap();
+    fs::create_dir_all(lang.path.parent().unwrap())
+        .await
+        .unwrap();
+    fs::write(&lang.path, b"{\"language.name\": \"Broken!\"}")
+        .await
+        .unwrap();
+
+    let issues = scheduler
+        .run(verify_assets_task(objects.clone()))
+        .await
+        .unwrap();
+
+    let kinds = issues
+        .iter()
+        .map(|issue| (issue.name.as_str(), issue.kind))
+        .collect::<HashMap<_, _>>();
+    assert_eq!(kinds.len(), 2);
+    assert_eq!(kinds["minecraft/lang/en_us.json"], AssetIssueKind::Corrupt);
+    assert_eq!(
+        kinds["minecraft/sounds/ambient/cave/cave1.ogg"],
+        AssetIssueKind::Missing
+    );
+    let sound = objects.iter().find(|o| o.hash == sha1_hex(SOUND)).unwrap();
+    assert!(
+        !sound.path.exists(),
+        "Verify only mode must not download anything"
+    );
+}
+
+fn legacy_index(is_virtual: bool, map_to_resources: bool) -> AssetIndexContent {
+    serde_json::from_value(json!({
+        "virtual": is_virtual,
+        "map_to_resources": map_to_resources,
+        "objects": {
+            "sound/step/grass1.ogg": { "hash": sha1_hex(SOUND), "size": SOUND.len() },
+            "lang/en_US.lang": { "hash": sha1_hex(LANG), "size": LANG.len() }
+        }
+    }))
+    .unwrap()
+}
+
+async fn store_objects(assets_dir: &AbsPathBuf) {
+    for content in [SOUND, LANG] {
+        let path = assets_dir
+            .join(ASSET_OBJECTS_DIR_NAME)
+            .join(&object_path(&sha1_hex(content))[1..]);
+        fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+        fs::write(path, content).await.unwrap();
+    }
+}
+
+#[test]
+fn test_asset_layout_from_index_flags() {
+    assert_eq!(legacy_index(false, false).layout(), AssetLayout::Modern);
+    assert_eq!(legacy_index(true, false).layout(), AssetLayout::Virtual);
+    assert_eq!(
+        legacy_index(true, true).layout(),
+        AssetLayout::MapToResources
+    );
+}
+
+#[tokio::test]
+async fn test_materialize_virtual_assets() {
+    let root = TempDir::new().unwrap();
+    let minecraft_dir = AbsPathBuf::new(root.path()).unwrap();
+    let assets_dir = minecraft_dir.join("assets");
+    store_objects(&assets_dir).await;
+
+    let game_assets = materialize_asset_layout(
+        &legacy_index(true, false),
+        &assets_dir,
+        "legacy",
+        &minecraft_dir,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(game_assets, assets_dir.join("virtual").join("legacy"));
+    assert_eq!(
+        fs::read(game_assets.join("sound/step/grass1.ogg"))
+            .await
+            .unwrap(),
+        SOUND
+    );
+}
+
+#[tokio::test]
+async fn test_materialize_resources_into_game_dir() {
+    let root = TempDir::new().unwrap();
+    let minecraft_dir = AbsPathBuf::new(root.path()).unwrap();
+    let assets_dir = minecraft_dir.join("assets");
+    let game_dir = minecraft_dir.join("versions").join("1.5.2");
+    store_objects(&assets_dir).await;
+    let lang_path = game_dir.join("resources").join("lang/en_US.lang");
+    fs::create_dir_all(lang_path.parent().unwrap())
+        .await
+        .unwrap();
+    fs::write(&lang_path, b"outdated").await.unwrap();
+
+    let game_assets = materialize_asset_layout(
+        &legacy_index(false, true),
+        &assets_dir,
+        "pre-1.6",
+        &game_dir,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(game_assets, game_dir.join("resources"));
+    assert_eq!(fs::read(&lang_path).await.unwrap(), LANG);
+    assert!(game_assets.join("sound/step/grass1.ogg").is_file());
+}
+
+#[tokio::test]
+async fn test_materialize_rejects_names_leaving_the_target_dir() {
+    let root = TempDir::new().unwrap();
+    let minecraft_dir = AbsPathBuf::new(root.path()).unwrap();
+    let assets_dir = minecraft_dir.join("assets");
+    store_objects(&assets_dir).await;
+    let index: AssetIndexContent = serde_json::from_value(json!({
+        "virtual": true,
+        "objects": {
+            "../../../escape.ogg": { "hash": sha1_hex(SOUND), "size": SOUND.len() }
+        }
+    }))
+    .unwrap();
+
+    let result = materialize_asset_layout(&index, &assets_dir, "legacy", &minecraft_dir).await;
+
+    assert!(result.is_err());
+    assert!(!minecraft_dir.join("escape.ogg").exists());
+}
+
+#[test]
+fn test_modern_layout_uses_assets_dir() {
+    let assets_dir = AbsPathBuf::new("/mc/assets").unwrap();
+    let game_dir = AbsPathBuf::new("/mc").unwrap();
+
+    assert_eq!(
+        get_game_assets_dir(AssetLayout::Modern, &assets_dir, "17", &game_dir),
+        assets_dir
+    );
+}
+
+const LOG_CONFIG: &[u8] = b"<Configuration><Appenders/></Configuration>";
+
+fn logging_config(url: &str, content: &[u8]) -> LoggingConfig {
+    serde_json::from_value(json!({
+        "argument": "-Dlog4j.configurationFile=${path}",
+        "file": {
+            "id": "client-1.12.xml",
+            "sha1": sha1_hex(content),
+            "size": content.len(),
+            "url": url
+        },
+        "type": "log4j2-xml"
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_download_log_config_into_log_configs() {
+    let server = TestServer::start(HashMap::from([(
+        "/client-1.12.xml".to_string(),
+        LOG_CONFIG.to_vec(),
+    )]))
+    .await;
+    let root = TempDir::new().unwrap();
+    let assets_dir = AbsPathBuf::new(root.path()).unwrap();
+    let url = format!("{}/client-1.12.xml", server.base_url);
+    let other_root = TempDir::new().unwrap();
+
+    let path = download_log_config(
+        &Client::new(),
+        &url,
+        &logging_config(&url, LOG_CONFIG),
+        &assets_dir,
+    )
+    .await
+    .unwrap();
+    let tampered = download_log_config(
+        &Client::new(),
+        &url,
+        &logging_config(&url, b"expected"),
+        &AbsPathBuf::new(other_root.path()).unwrap(),
+    )
+    .await;
+
+    assert_eq!(path, assets_dir.join("log_configs").join("client-1.12.xml"));
+    assert_eq!(fs::read(&path).await.unwrap(), LOG_CONFIG);
+    assert!(tampered.is_err(), "Log config must match its sha1");
+}
+
+#[tokio::test]
+async fn test_download_log_config_task_uses_the_mirror() {
+    let server = TestServer::start(HashMap::from([(
+        "/piston-data/v1/objects/abc/client-1.12.xml".to_string(),
+        LOG_CONFIG.to_vec(),
+    )]))
+    .await;
+    let root = TempDir::new().unwrap();
+    let assets_dir = AbsPathBuf::new(root.path()).unwrap();
+    let catalog = Arc::new(VersionCatalogClient::new(Client::new(), &server.base_url));
+    let config = logging_config(
+        "https://piston-data.mojang.com/v1/objects/abc/client-1.12.xml",
+        LOG_CONFIG,
+    );
+
+    Scheduler::new(1)
+        .run(download_log_config_task(
+            Client::new(),
+            catalog,
+            Some(config.clone()),
+            assets_dir.clone(),
+        ))
+        .await
+        .unwrap();
+
+    let path = get_log_config_path(&assets_dir, &config);
+    assert_eq!(fs::read(&path).await.unwrap(), LOG_CONFIG);
+}
+
+#[test]
+fn test_logging_argument_substitutes_path() {
+    let config = logging_config("https://piston-data.mojang.com/client-1.12.xml", LOG_CONFIG);
+    let assets_dir = AbsPathBuf::new("/mc/assets").unwrap();
+    let path = get_log_config_path(&assets_dir, &config);
+
+    assert_eq!(
+        get_logging_argument(Some(&config), &path),
+        format!("-Dlog4j.configurationFile={}", path.display())
+    );
+    assert_eq!(
+        get_logging_argument(None, &AbsPathBuf::new("/custom/log4j2.xml").unwrap()),
+        format!(
+            "-Dlog4j.configurationFile={}",
+            AbsPathBuf::new("/custom/log4j2.xml").unwrap().display()
+        )
+    );
+}
@@ -0,0 +1,178 @@
+use crate::constants::minecraft_dir::VERSIONS_DIR_NAME;
+use crate::constants::network::{
+    MOJANG_MIRROR_PATHS, OFFICIAL_META_BASE_URL, VERSION_MANIFEST_PATH,
+};
+use crate::game_installer::download::{download_file, is_file_intact};
+use crate::game_installer::models::VersionCatalog;
+use crate::game_resolver::VersionManifest;
+use crate::utils::abs_path_buf::AbsPathBuf;
+use anyhow::{Context, Result, anyhow};
+use log::{info, warn};
+use parking_lot::{Mutex, RwLock};
+use reqwest::Client;
+use std::path::PathBuf;
+use tokio::fs;
+use url::Url;
+
+pub struct VersionCatalogClient {
+    client: Client,
+    base_url: RwLock<String>,
+    cache_path: Option<PathBuf>,
+    cached: Mutex<Option<VersionCatalog>>,
+}
+
+impl VersionCatalogClient {
+    pub fn new(client: Client, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: RwLock::new(base_url.into()),
+            cache_path: None,
+            cached: Mutex::new(None),
+        }
+    }
+
+    pub fn with_cache_path(mut self, cache_path: PathBuf) -> Self {
+        self.cache_path = Some(cache_path);
+        self
+    }
+
+    pub fn base_url(&self) -> String {
+        self.base_url.read().clone()
+    }
+
+    pub fn set_base_url(&self, base_url: impl Into<String>) {
+        *self.base_url.write() = base_url.into();
+        self.cached.lock().take();
+    }
+
+    // urls inside the catalog always point to mojang, route them through the configured mirror
+    pub fn resolve_url(&self, url: &str) -> String {
+        let base_url = self.base_url();
+        let base_url = base_url.trim_end_matches('/');
+        if base_url == OFFICIAL_META_BASE_URL {
+            return url.to_string();
+        }
+        let Ok(parsed) = Url::parse(url) else {
+            return url.to_string();
+        };
+        let Some((_, mirror_path)) = MOJANG_MIRROR_PATHS
+            .iter()
+            .find(|(host, _)| parsed.host_str() == Some(*host))
+        else {
+            return url.to_string();
+        };
+        let mut resolved = base_url.to_string();
+        if !mirror_path.is_empty() {
+            resolved.push('/');
+            resolved.push_str(mirror_path);
+        }
+        resolved.push_str(parsed.path());
+        if let Some(query) = parsed.query() {
+            resolved.push('?');
+            resolved.push_str(query);
+        }
+        resolved
+    }
+
+    pub async fn get_catalog(&self, refresh: bool) -> Result<VersionCatalog> {
+        if !refresh && let Some(catalog) = self.cached.lock().clone() {
+            return Ok(catalog);
+        }
+
+        let catalog = match self.fetch_catalog().await {
+            Ok(catalog) => {
+                self.save_cache(&catalog).await;
+                catalog
+            }
+            Err(e) => {
+                warn!("Failed to fetch version catalog, trying local cache: {e:?}");
+                self.load_cache().await.ok_or(e)?
+            }
+        };
+
+        *self.cached.lock() = Some(catalog.clone());
+        Ok(catalog)
+    }
+
+    async fn fetch_catalog(&self) -> Result<VersionCatalog> {
+        let url = format!(
+            "{}/{VERSION_MANIFEST_PATH}",
+            self.base_url().trim_end_matches('/')
+        );
+        let response = self.client.get(&url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Fail to fetch version catalog from {url}: {}",
+                response.status()
+            ));
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn save_cache(&self, catalog: &VersionCatalog) {
+        let Some(cache_path) = &self.cache_path else {
+            return;
+        };
+        let result = async {
+            if let Some(parent) = cache_path.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::write(cache_path, serde_json::to_vec(catalog)?).await?;
+            anyhow::Ok(())
+        };
+        if let Err(e) = result.await {
+            warn!("Failed to cache version catalog: {e:?}");
+        }
+    }
+
+    async fn load_cache(&self) -> Option<VersionCatalog> {
+        let content = fs::read(self.cache_path.as_ref()?).await.ok()?;
+        serde_json::from_slice(&content)
+            .inspect_err(|e| warn!("Cached version catalog is corrupted: {e}"))
+            .ok()
+    }
+
+    pub async fn install_version(
+        &self,
+        minecraft_dir: &AbsPathBuf,
+        version_id: &str,
+    ) -> Result<VersionManifest> {
+        let catalog = self.get_catalog(false).await?;
+        let entry = catalog
+            .find(version_id)
+            .with_context(|| format!("Version '{version_id}' not found in catalog"))?;
+
+        let version_dir = minecraft_dir.join(VERSIONS_DIR_NAME).join(version_id);
+        let json_path = version_dir.join(format!("{version_id}.json"));
+        if !is_file_intact(&json_path, Some(&entry.sha1), None).await {
+            info!("Downloading version json of {version_id}");
+            let url = self.resolve_url(&entry.url);
+            download_file(&self.client, &url, &json_path, Some(&entry.sha1), None).await?;
+        }
+
+        let content = fs::read(&json_path).await?;
+        let manifest: VersionManifest = serde_json::from_slice(&content)
+            .with_context(|| format!("Invalid version json of '{version_id}'"))?;
+
+        let Some(downloads) = &manifest.downloads else {
+            warn!("Version '{version_id}' does not provide a client jar");
+            return Ok(manifest);
+        };
+        let client = &downloads.client;
+        let jar_path = version_dir.join(format!("{version_id}.jar"));
+        if !is_file_intact(&jar_path, Some(&client.sha1), Some(client.size)).await {
+            info!("Downloading client jar of {version_id}");
+            let url = self.resolve_url(&client.url);
+            download_file(
+                &self.client,
+                &url,
+                &jar_path,
+                Some(&client.sha1),
+                Some(client.size),
+            )
+            .await?;
+        }
+
+        Ok(manifest)
+    }
+}
@@ -0,0 +1,93 @@
+use crate::constants::file_system::CACHE_DIR_NAME;
+use crate::constants::minecraft_dir::LIBRARIES_DIR_NAME;
+use crate::game_installer::catalog::VersionCatalogClient;
+use crate::game_installer::libraries::{collect_library_downloads, download_libraries_task};
+use crate::game_installer::models::VersionCatalog;
+use crate::game_launcher::models::RuleContext;
+use crate::game_resolver::models::Library;
+use crate::game_resolver::resolve::resolve_installed_version;
+use crate::scheduler::Scheduler;
+use crate::settings::modules::download::DownloadSettings;
+use crate::settings::store::SettingsStore;
+use crate::utils::abs_path_buf::AbsPathBuf;
+use crate::utils::command::CommandResult;
+use crate::utils::dirs::app_dir;
+use anyhow::Result;
+use macros::command;
+use reqwest::Client;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{App, Manager, State};
+
+const VERSION_CATALOG_CACHE_FILE_NAME: &str = "version_manifest_v2.json";
+
+pub fn setup_game_installer(app: &mut App) -> Result<()> {
+    let store = app.state::<Arc<SettingsStore>>();
+    let settings = store.get::<DownloadSettings>();
+    let cache_path = app_dir()?
+        .join(CACHE_DIR_NAME)
+        .join(VERSION_CATALOG_CACHE_FILE_NAME);
+    let catalog = VersionCatalogClient::new(Client::new(), settings.meta_base_url)
+        .with_cache_path(cache_path);
+    app.manage(Arc::new(catalog));
+    Ok(())
+}
+
+#[command]
+pub async fn get_version_catalog(
+    catalog: State<'_, Arc<VersionCatalogClient>>,
+    refresh: bool,
+) -> CommandResult<VersionCatalog> {
+    Ok(catalog.get_catalog(refresh).await?)
+}
+
+#[command]
+pub async fn install_vanilla_version(
+    catalog: State<'_, Arc<VersionCatalogClient>>,
+    scheduler: State<'_, Scheduler>,
+    minecraft_dir: String,
+    version_id: String,
+) -> CommandResult<()> {
+    let minecraft_dir = AbsPathBuf::new(&minecraft_dir)?;
+    let manifest = catalog.install_version(&minecraft_dir, &version_id).await?;
+    install_libraries(&scheduler, &minecraft_dir, &manifest.libraries).await?;
+    Ok(())
+}
+
+#[command]
+pub async fn repair_version(
+    catalog: State<'_, Arc<VersionCatalogClient>>,
+    scheduler: State<'_, Scheduler>,
+    minecraft_dir: String,
+    version_id: String,
+) -> CommandResult<()> {
+    let minecraft_dir = AbsPathBuf::new(&minecraft_dir)?;
+    let is_vanilla = catalog
+        .get_catalog(false)
+        .await
+        .is_ok_and(|c| c.find(&version_id).is_some());
+    if is_vanilla {
+        catalog.install_version(&minecraft_dir, &version_id).await?;
+    }
+
+    let manifest = resolve_installed_version(minecraft_dir.clone(), &version_id).await?;
+    install_libraries(&scheduler, &minecraft_dir, &manifest.libraries).await?;
+    Ok(())
+}
+
+pub(crate) async fn install_libraries(
+    scheduler: &Scheduler,
+    minecraft_dir: &AbsPathBuf,
+    libraries: &[Library],
+) -> Result<()> {
+    let rule_context = RuleContext {
+        os_info: os_info::get(),
+        user_features: HashMap::new(),
+    };
+    let libraries_dir = minecraft_dir.join(LIBRARIES_DIR_NAME);
+    let downloads = collect_library_downloads(libraries, &libraries_dir, &rule_context);
+    scheduler
+        .run(download_libraries_task(Client::new(), downloads))
+        .await?;
+    Ok(())
+}
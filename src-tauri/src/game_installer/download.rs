@@ -0,0 +1,202 @@
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Error)]
+pub enum DownloadError {
+    #[error("Unexpected status {status} when downloading {url}")]
+    UnexpectedStatus { url: String, status: u16 },
+    #[error("Size mismatch for {url}: expected {expected} bytes, got {actual}")]
+    SizeMismatch {
+        url: String,
+        expected: u64,
+        actual: u64,
+    },
+    #[error("Checksum mismatch for {url}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+pub async fn download_file(
+    client: &Client,
+    url: &str,
+    dest: &Path,
+    sha1: Option<&str>,
+    size: Option<u64>,
+) -> Result<()> {
+    let mut response = client.get(url).send().await?;
+    if !response.status().is_success() {
+        return Err(DownloadError::UnexpectedStatus {
+            url: url.to_string(),
+            status: response.status().as_u16(),
+        }
+        .into());
+    }
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+
+    // write to a temporary file first, so a broken download never replaces a good file
+    let mut part_path = dest.as_os_str().to_owned();
+    part_path.push(".part");
+    let part_path = PathBuf::from(part_path);
+    let mut file = fs::File::create(&part_path)
+        .await
+        .with_context(|| format!("Failed to create file {}", part_path.display()))?;
+    let mut hasher = Sha1::new();
+    let mut written = 0u64;
+    while let Some(chunk) = response.chunk().await? {
+        hasher.update(&chunk);
+        file.write_all(&chunk).await?;
+        written += chunk.len() as u64;
+    }
+    file.flush().await?;
+    drop(file);
+
+    if let Err(e) = check_integrity(url, hasher, written, sha1, size) {
+        let _ = fs::remove_file(&part_path).await;
+        return Err(e.into());
+    }
+
+    fs::rename(&part_path, dest)
+        .await
+        .with_context(|| format!("Failed to move download to {}", dest.display()))?;
+    Ok(())
+}
+
+/// Fetches and parses a json document, checking its sha1 first when one is known.
+pub async fn fetch_json<T: DeserializeOwned>(
+    client: &Client,
+    url: &str,
+    sha1: Option<&str>,
+) -> Result<T> {
+    let response = client.get(url).send().await?;
+    if !response.status().is_success() {
+        return Err(DownloadError::UnexpectedStatus {
+            url: url.to_string(),
+            status: response.status().as_u16(),
+        }
+        .into());
+    }
+    let content = response.bytes().await?;
+    let mut hasher = Sha1::new();
+    hasher.update(&content);
+    check_integrity(url, hasher, content.len() as u64, sha1, None)?;
+    serde_json::from_slice(&content).with_context(|| format!("Invalid json from {url}"))
+}
+
+fn check_integrity(
+    url: &str,
+    hasher: Sha1,
+    actual_size: u64,
+    sha1: Option<&str>,
+    size: Option<u64>,
+) -> Result<(), DownloadError> {
+    if let Some(expected) = size
+        && expected != actual_size
+    {
+        return Err(DownloadError::SizeMismatch {
+            url: url.to_string(),
+            expected,
+            actual: actual_size,
+        });
+    }
+
+    let actual = hex::encode(hasher.finalize());
+    if let Some(expected) = sha1
+        && !expected.eq_ignore_ascii_case(&actual)
+    {
+        return Err(DownloadError::ChecksumMismatch {
+            url: url.to_string(),
+            expected: expected.to_string(),
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
+pub async fn file_sha1(path: &Path) -> Result<String> {
+    file_digest::<Sha1>(path).await
+}
+
+pub async fn file_sha256(path: &Path) -> Result<String> {
+    file_digest::<Sha256>(path).await
+}
+
+async fn file_digest<D: Digest>(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path).await?;
+    let mut hasher = D::new();
+    let mut buffer = vec![0; READ_BUFFER_SIZE];
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+pub async fn is_file_intact(path: &Path, sha1: Option<&str>, size: Option<u64>) -> bool {
+    let Ok(metadata) = fs::metadata(path).await else {
+        return false;
+    };
+    if !metadata.is_file() || size.is_some_and(|size| size != metadata.len()) {
+        return false;
+    }
+    match sha1 {
+        Some(expected) => file_sha1(path)
+            .await
+            .is_ok_and(|actual| expected.eq_ignore_ascii_case(&actual)),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    const HELLO_SHA1: &str = "aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d";
+
+    #[tokio::test]
+    async fn test_is_file_intact() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hello.txt");
+        fs::write(&path, "hello").await.unwrap();
+
+        assert!(is_file_intact(&path, Some(HELLO_SHA1), Some(5)).await);
+        assert!(is_file_intact(&path, None, None).await);
+        assert!(!is_file_intact(&path, Some(HELLO_SHA1), Some(6)).await);
+        assert!(!is_file_intact(&path, Some("0000"), None).await);
+        assert!(!is_file_intact(&dir.path().join("missing"), None, None).await);
+    }
+
+    #[test]
+    fn test_check_integrity_reports_mismatch() {
+        let mut hasher = Sha1::new();
+        hasher.update(b"hello");
+
+        let result = check_integrity("http://test/hello", hasher, 5, Some("0000"), Some(5));
+
+        assert!(matches!(
+            result,
+            Err(DownloadError::ChecksumMismatch { actual, .. }) if actual == HELLO_SHA1
+        ));
+    }
+}
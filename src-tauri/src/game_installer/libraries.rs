@@ -0,0 +1,281 @@
+use crate::game_installer::download::{download_file, is_file_intact};
+use crate::game_launcher::models::RuleContext;
+use crate::game_launcher::natives::{native_classifier, native_jar_path};
+use crate::game_launcher::rule::should_apply_rules;
+use crate::game_resolver::models::{DownloadFile, Library};
+use crate::scheduler::{Task, parallel, task};
+use crate::utils::abs_path_buf::AbsPathBuf;
+use anyhow::{Result, bail};
+use reqwest::Client;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LibraryDownload {
+    pub name: String,
+    pub url: String,
+    pub path: AbsPathBuf,
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
+}
+
+pub fn collect_library_downloads(
+    libraries: &[Library],
+    libraries_dir: &AbsPathBuf,
+    rule_context: &RuleContext,
+) -> Vec<LibraryDownload> {
+    let mut seen_paths = HashSet::new();
+    libraries
+        .iter()
+        .filter(|library| {
+            library
+                .rules
+                .clone()
+                .is_none_or(|rules| should_apply_rules(rules, rule_context.clone()))
+        })
+        .flat_map(|library| {
+            [
+                artifact_download(library, libraries_dir),
+                native_download(library, libraries_dir, rule_context),
+            ]
+        })
+        .flatten()
+        .filter(|download| seen_paths.insert(download.path.clone()))
+        .collect()
+}
+
+fn artifact_download(library: &Library, libraries_dir: &AbsPathBuf) -> Option<LibraryDownload> {
+    let Some(artifact) = library.downloads.as_ref().and_then(|d| d.artifact.as_ref()) else {
+        return maven_download(library, libraries_dir);
+    };
+    let fallback_path = library.get_jar_path(libraries_dir.clone());
+    to_library_download(&library.name, artifact, libraries_dir, fallback_path)
+}
+
+// mod loader libraries only name a maven repository, and come without a checksum
+fn maven_download(library: &Library, libraries_dir: &AbsPathBuf) -> Option<LibraryDownload> {
+    let repository = library.url.as_ref()?;
+    let maven_path = library.get_maven_path()?;
+    Some(LibraryDownload {
+        name: library.name.clone(),
+        url: format!("{}/{maven_path}", repository.trim_end_matches('/')),
+        path: library.get_jar_path(libraries_dir.clone())?,
+        sha1: None,
+        size: None,
+    })
+}
+
+fn native_download(
+    library: &Library,
+    libraries_dir: &AbsPathBuf,
+    rule_context: &RuleContext,
+) -> Option<LibraryDownload> {
+    let classifier = native_classifier(library, rule_context)?;
+    let file = library
+        .downloads
+        .as_ref()?
+        .classifiers
+        .as_ref()?
+        .get(&classifier)?;
+    let name = format!("{}:{classifier}", library.name);
+    let fallback_path = native_jar_path(library, &classifier, libraries_dir);
+    to_library_download(&name, file, libraries_dir, fallback_path)
+}
+
+fn to_library_download(
+    name: &str,
+    file: &DownloadFile,
+    libraries_dir: &AbsPathBuf,
+    fallback_path: Option<AbsPathBuf>,
+) -> Option<LibraryDownload> {
+    let path = match &file.path {
+        Some(path) => libraries_dir.join(path),
+        None => fallback_path?,
+    };
+    Some(LibraryDownload {
+        name: name.to_string(),
+        url: file.url.clone(),
+        path,
+        sha1: Some(file.sha1.clone()).filter(|sha1| !sha1.is_empty()),
+        size: Some(file.size).filter(|size| *size > 0),
+    })
+}
+
+pub fn download_libraries_task(
+    client: Client,
+    downloads: Vec<LibraryDownload>,
+) -> impl Task<Input = (), Output = Vec<()>> {
+    let tasks = downloads.into_iter().map(move |download| {
+        let client = client.clone();
+        let weight = download.size.map_or(1, |size| (size / 1024).max(1));
+        task(download.name.clone(), move |_: ()| {
+            download_library(client.clone(), download.clone())
+        })
+        .with_weight(weight)
+    });
+    parallel("Download libraries").extend(tasks).build()
+}
+
+async fn download_library(client: Client, download: LibraryDownload) -> Result<()> {
+    let sha1 = download.sha1.as_deref();
+    if is_file_intact(&download.path, sha1, download.size).await {
+        return Ok(());
+    }
+    if download.url.is_empty() {
+        bail!(
+            "Library {} is missing and has no download url",
+            download.name
+        );
+    }
+    download_file(&client, &download.url, &download.path, sha1, download.size).await
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::utils::os_info::mock_info;
+    use os_info::Type;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn libraries() -> Vec<Library> {
+        serde_json::from_value(json!([
+            {
+                "name": "com.mojang:authlib:1.5.21",
+                "downloads": {
+                    "artifact": {
+                        "path": "com/mojang/authlib/1.5.21/authlib-1.5.21.jar",
+                        "sha1": "aefba0d5b53fbcb70860bc8046ab95d5854c07a5",
+                        "size": 64412,
+                        "url": "https://libraries.minecraft.net/com/mojang/authlib/1.5.21/authlib-1.5.21.jar"
+                    }
+                }
+            },
+            {
+                "name": "org.lwjgl:lwjgl:3.3.2:natives-windows",
+                "downloads": {
+                    "artifact": {
+                        "path": "org/lwjgl/lwjgl/3.3.2/lwjgl-3.3.2-natives-windows.jar",
+                        "sha1": "0000000000000000000000000000000000000000",
+                        "size": 1,
+                        "url": "https://libraries.minecraft.net/lwjgl-natives-windows.jar"
+                    }
+                },
+                "rules": [{ "action": "allow", "os": { "name": "windows" } }]
+            },
+            {
+                "name": "org.lwjgl.lwjgl:lwjgl-platform:2.9.0",
+                "downloads": {
+                    "classifiers": {
+                        "natives-linux": {
+                            "path": "org/lwjgl/lwjgl/lwjgl-platform/2.9.0/lwjgl-platform-2.9.0-natives-linux.jar",
+                            "sha1": "2ba5dcb11048147f1a74eff2deb192c001321f77",
+                            "size": 569061,
+                            "url": "https://libraries.minecraft.net/lwjgl-platform-2.9.0-natives-linux.jar"
+                        },
+                        "natives-windows-64": {
+                            "path": "org/lwjgl/lwjgl/lwjgl-platform/2.9.0/lwjgl-platform-2.9.0-natives-windows-64.jar",
+                            "sha1": "3f11873dc8e84c854ec7c5a8fd2e869f8aaef764",
+                            "size": 609967,
+                            "url": "https://libraries.minecraft.net/lwjgl-platform-2.9.0-natives-windows-64.jar"
+                        }
+                    }
+                },
+                "natives": { "linux": "natives-linux", "windows": "natives-windows-${arch}" }
+            },
+            {
+                "name": "com.mojang:authlib:1.5.21",
+                "downloads": {
+                    "artifact": {
+                        "path": "com/mojang/authlib/1.5.21/authlib-1.5.21.jar",
+                        "sha1": "aefba0d5b53fbcb70860bc8046ab95d5854c07a5",
+                        "size": 64412,
+                        "url": "https://libraries.minecraft.net/com/mojang/authlib/1.5.21/authlib-1.5.21.jar"
+                    }
+                }
+            },
+            { "name": "net.minecraft:launchwrapper:1.12" }
+        ]))
+        .unwrap()
+    }
+
+    fn collect_for(os_type: Type) -> Vec<String> {
+        let libraries_dir = AbsPathBuf::new("/mc/libraries").unwrap();
+        let rule_context = RuleContext {
+            os_info: mock_info(os_type, "1.0", "x86_64"),
+            user_features: HashMap::new(),
+        };
+        collect_library_downloads(&libraries(), &libraries_dir, &rule_context)
+            .into_iter()
+            .map(|d| d.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_collect_library_downloads_on_linux() {
+        assert_eq!(
+            collect_for(Type::Linux),
+            [
+                "com.mojang:authlib:1.5.21",
+                "org.lwjgl.lwjgl:lwjgl-platform:2.9.0:natives-linux"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_library_downloads_on_windows() {
+        assert_eq!(
+            collect_for(Type::Windows),
+            [
+                "com.mojang:authlib:1.5.21",
+                "org.lwjgl:lwjgl:3.3.2:natives-windows",
+                "org.lwjgl.lwjgl:lwjgl-platform:2.9.0:natives-windows-64"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_library_path_follows_download_path() {
+        let libraries_dir = AbsPathBuf::new("/mc/libraries").unwrap();
+        let rule_context = RuleContext {
+            os_info: mock_info(Type::Linux, "1.0", "x86_64"),
+            user_features: HashMap::new(),
+        };
+
+        let downloads = collect_library_downloads(&libraries(), &libraries_dir, &rule_context);
+
+        assert_eq!(
+            downloads[1].path,
+            libraries_dir.join(
+                "org/lwjgl/lwjgl/lwjgl-platform/2.9.0/lwjgl-platform-2.9.0-natives-linux.jar"
+            )
+        );
+        assert_eq!(downloads[1].size, Some(569061));
+    }
+
+    #[test]
+    fn test_maven_library_downloads_from_its_repository() {
+        let libraries_dir = AbsPathBuf::new("/mc/libraries").unwrap();
+        let rule_context = RuleContext {
+            os_info: mock_info(Type::Linux, "1.0", "x86_64"),
+            user_features: HashMap::new(),
+        };
+        let libraries: Vec<Library> = serde_json::from_value(json!([
+            { "name": "net.fabricmc:fabric-loader:0.16.9", "url": "https://maven.fabricmc.net/" }
+        ]))
+        .unwrap();
+
+        let downloads = collect_library_downloads(&libraries, &libraries_dir, &rule_context);
+
+        assert_eq!(
+            downloads,
+            [LibraryDownload {
+                name: "net.fabricmc:fabric-loader:0.16.9".to_string(),
+                url: "https://maven.fabricmc.net/net/fabricmc/fabric-loader/0.16.9/fabric-loader-0.16.9.jar".to_string(),
+                path: libraries_dir.join("net/fabricmc/fabric-loader/0.16.9/fabric-loader-0.16.9.jar"),
+                sha1: None,
+                size: None,
+            }]
+        );
+    }
+}
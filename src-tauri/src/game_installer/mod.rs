@@ -0,0 +1,6 @@
+pub mod catalog;
+pub mod commands;
+pub mod download;
+pub mod libraries;
+pub mod models;
+mod tests;
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionCatalog {
+    pub latest: LatestVersions,
+    pub versions: Vec<CatalogEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LatestVersions {
+    pub release: String,
+    pub snapshot: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogEntry {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub version_type: CatalogVersionType,
+    pub url: String,
+    pub time: String,
+    pub release_time: String,
+    pub sha1: String,
+    #[serde(default)]
+    pub compliance_level: u32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Hash, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogVersionType {
+    Release,
+    Snapshot,
+    OldBeta,
+    OldAlpha,
+    #[serde(other)]
+    Unknown,
+}
+
+impl VersionCatalog {
+    pub fn find(&self, id: &str) -> Option<&CatalogEntry> {
+        self.versions.iter().find(|v| v.id == id)
+    }
+
+    pub fn versions_of(&self, version_type: CatalogVersionType) -> Vec<&CatalogEntry> {
+        self.versions
+            .iter()
+            .filter(|v| v.version_type == version_type)
+            .collect()
+    }
+}
@@ -0,0 +1,269 @@
+#![cfg_attr(coverage_nightly, coverage(off))]
+#![cfg(test)]
+
+use crate::constants::minecraft_dir::VERSIONS_DIR_NAME;
+use crate::constants::network::{OFFICIAL_META_BASE_URL, VERSION_MANIFEST_PATH};
+use crate::game_installer::catalog::VersionCatalogClient;
+use crate::game_installer::download::file_sha1;
+use crate::game_installer::libraries::{LibraryDownload, download_libraries_task};
+use crate::game_installer::models::CatalogVersionType;
+use crate::scheduler::Scheduler;
+use crate::scheduler::models::TaskState;
+use crate::utils::abs_path_buf::AbsPathBuf;
+use crate::utils::test_server::TestServer;
+use reqwest::Client;
+use serde_json::json;
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use tempfile::TempDir;
+
+const CLIENT_JAR: &[u8] = b"not really a jar";
+const VERSION_JSON_PATH: &str = "/v1/packages/1.20.4.json";
+const CLIENT_JAR_PATH: &str = "/v1/objects/client.jar";
+
+fn sha1_hex(data: &[u8]) -> String {
+    hex::encode(Sha1::digest(data))
+}
+
+fn version_json(jar: &[u8]) -> Vec<u8> {
+    json!({
+        "id": "1.20.4",
+        "type": "release",
+        "time": "2023-12-07T12:56:20+00:00",
+        "releaseTime": "2023-12-07T12:56:20+00:00",
+        "mainClass": "net.minecraft.client.main.Main",
+        "libraries": [],
+        "downloads": {
+            "client": {
+                "sha1": sha1_hex(jar),
+                "size": jar.len(),
+                "url": format!("https://piston-data.mojang.com{CLIENT_JAR_PATH}")
+            }
+        }
+    })
+    .to_string()
+    .into_bytes()
+}
+
+fn catalog_json(version_json: &[u8]) -> Vec<u8> {
+    json!({
+        "latest": { "release": "1.20.4", "snapshot": "24w14a" },
+        "versions": [
+            {
+                "id": "24w14a",
+                "type": "snapshot",
+                "url": "https://piston-meta.mojang.com/v1/packages/24w14a.json",
+                "time": "2024-04-03T12:00:00+00:00",
+                "releaseTime": "2024-04-03T12:00:00+00:00",
+                "sha1": "0000000000000000000000000000000000000000",
+                "complianceLevel": 1
+            },
+            {
+                "id": "1.20.4",
+                "type": "release",
+                "url": format!("https://piston-meta.mojang.com{VERSION_JSON_PATH}"),
+                "time": "2023-12-07T12:56:20+00:00",
+                "releaseTime": "2023-12-07T12:56:20+00:00",
+                "sha1": sha1_hex(version_json),
+                "complianceLevel": 1
+            },
+            {
+                "id": "b1.7.3",
+                "type": "old_beta",
+                "url": "https://piston-meta.mojang.com/v1/packages/b1.7.3.json",
+                "time": "2011-07-07T22:00:00+00:00",
+                "releaseTime": "2011-07-07T22:00:00+00:00",
+                "sha1": "0000000000000000000000000000000000000000",
+                "complianceLevel": 0
+            },
+            {
+                "id": "a1.0.4",
+                "type": "old_alpha",
+                "url": "https://piston-meta.mojang.com/v1/packages/a1.0.4.json",
+                "time": "2010-07-12T22:00:00+00:00",
+                "releaseTime": "2010-07-12T22:00:00+00:00",
+                "sha1": "0000000000000000000000000000000000000000"
+            }
+        ]
+    })
+    .to_string()
+    .into_bytes()
+}
+
+async fn start_server(jar_served: &[u8]) -> TestServer {
+    let version_json = version_json(CLIENT_JAR);
+    let routes = HashMap::from([
+        (
+            format!("/{VERSION_MANIFEST_PATH}"),
+            catalog_json(&version_json),
+        ),
+        (VERSION_JSON_PATH.to_string(), version_json),
+        (
+            format!("/piston-data{CLIENT_JAR_PATH}"),
+            jar_served.to_vec(),
+        ),
+    ]);
+    TestServer::start(routes).await
+}
+
+#[tokio::test]
+async fn test_catalog_groups_versions_by_type() {
+    let server = start_server(CLIENT_JAR).await;
+    let client = VersionCatalogClient::new(Client::new(), &server.base_url);
+
+    let catalog = client.get_catalog(false).await.unwrap();
+
+    assert_eq!(catalog.latest.release, "1.20.4");
+    let ids = |version_type| {
+        catalog
+            .versions_of(version_type)
+            .iter()
+            .map(|v| v.id.as_str())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(CatalogVersionType::Release), ["1.20.4"]);
+    assert_eq!(ids(CatalogVersionType::Snapshot), ["24w14a"]);
+    assert_eq!(ids(CatalogVersionType::OldBeta), ["b1.7.3"]);
+    assert_eq!(ids(CatalogVersionType::OldAlpha), ["a1.0.4"]);
+    assert_eq!(
+        catalog.find("a1.0.4").unwrap().release_time,
+        "2010-07-12T22:00:00+00:00"
+    );
+}
+
+#[tokio::test]
+async fn test_install_version_through_configured_base_url() {
+    let server = start_server(CLIENT_JAR).await;
+    let client = VersionCatalogClient::new(Client::new(), &server.base_url);
+    let root = TempDir::new().unwrap();
+    let minecraft_dir = AbsPathBuf::new(root.path()).unwrap();
+
+    let manifest = client
+        .install_version(&minecraft_dir, "1.20.4")
+        .await
+        .unwrap();
+
+    let version_dir = minecraft_dir.join(VERSIONS_DIR_NAME).join("1.20.4");
+    assert_eq!(manifest.id, "1.20.4");
+    assert!(version_dir.join("1.20.4.json").is_file());
+    assert_eq!(
+        file_sha1(&version_dir.join("1.20.4.jar")).await.unwrap(),
+        sha1_hex(CLIENT_JAR)
+    );
+}
+
+#[tokio::test]
+async fn test_install_version_rejects_corrupted_jar() {
+    let server = start_server(b"tampered jar").await;
+    let client = VersionCatalogClient::new(Client::new(), &server.base_url);
+    let root = TempDir::new().unwrap();
+    let minecraft_dir = AbsPathBuf::new(root.path()).unwrap();
+
+    let result = client.install_version(&minecraft_dir, "1.20.4").await;
+
+    assert!(result.is_err(), "Corrupted jar should be rejected");
+    let version_dir = minecraft_dir.join(VERSIONS_DIR_NAME).join("1.20.4");
+    assert!(!version_dir.join("1.20.4.jar").exists());
+}
+
+#[tokio::test]
+async fn test_catalog_falls_back_to_cache_when_offline() {
+    let server = start_server(CLIENT_JAR).await;
+    let cache_dir = TempDir::new().unwrap();
+    let cache_path = cache_dir.path().join("version_manifest_v2.json");
+    let client = VersionCatalogClient::new(Client::new(), &server.base_url)
+        .with_cache_path(cache_path.clone());
+    let online = client.get_catalog(false).await.unwrap();
+    drop(server);
+
+    let offline_client =
+        VersionCatalogClient::new(Client::new(), "http://127.0.0.1:9").with_cache_path(cache_path);
+    let offline = offline_client.get_catalog(true).await.unwrap();
+
+    assert_eq!(online, offline);
+}
+
+#[test]
+fn test_resolve_url_only_rewrites_mojang_hosts() {
+    let client = VersionCatalogClient::new(Client::new(), "https://mirror.example.com/");
+
+    assert_eq!(
+        client.resolve_url("https://piston-meta.mojang.com/v1/packages/abc/1.20.4.json"),
+        "https://mirror.example.com/v1/packages/abc/1.20.4.json"
+    );
+    assert_eq!(
+        client.resolve_url("https://piston-data.mojang.com/v1/objects/abc/client.jar"),
+        "https://mirror.example.com/piston-data/v1/objects/abc/client.jar"
+    );
+    assert_eq!(
+        client.resolve_url("https://launcher.mojang.com/v1/objects/abc/client.jar"),
+        "https://mirror.example.com/launcher/v1/objects/abc/client.jar"
+    );
+    assert_eq!(
+        client.resolve_url("https://maven.fabricmc.net/net/fabricmc/a.jar"),
+        "https://maven.fabricmc.net/net/fabricmc/a.jar"
+    );
+}
+
+#[test]
+fn test_resolve_url_keeps_urls_with_official_base() {
+    let client = VersionCatalogClient::new(Client::new(), OFFICIAL_META_BASE_URL);
+    let url = "https://piston-data.mojang.com/v1/objects/abc/client.jar";
+
+    assert_eq!(client.resolve_url(url), url);
+}
+
+#[tokio::test]
+async fn test_download_libraries_task_verifies_and_skips_valid_files() {
+    let good: &[u8] = b"good library";
+    let server = TestServer::start(HashMap::from([
+        ("/good.jar".to_string(), good.to_vec()),
+        ("/bad.jar".to_string(), b"tampered".to_vec()),
+    ]))
+    .await;
+    let base_url = server.base_url.clone();
+    let root = TempDir::new().unwrap();
+    let libraries_dir = AbsPathBuf::new(root.path()).unwrap();
+    let library = |name: &str, file: &str, content: &[u8]| LibraryDownload {
+        name: name.to_string(),
+        url: format!("{base_url}/{file}"),
+        path: libraries_dir.join(file),
+        sha1: Some(sha1_hex(content)),
+        size: Some(content.len() as u64),
+    };
+    let scheduler = Scheduler::new(4);
+
+    let good_task = download_libraries_task(Client::new(), vec![library("good", "good.jar", good)]);
+    scheduler.run(good_task).await.unwrap();
+    let bad_task = download_libraries_task(
+        Client::new(),
+        vec![library("bad", "bad.jar", b"expected content")],
+    );
+    let bad_result = scheduler.run(bad_task).await;
+
+    assert_eq!(
+        file_sha1(&libraries_dir.join("good.jar")).await.unwrap(),
+        sha1_hex(good)
+    );
+    assert!(bad_result.is_err(), "Corrupted library should be rejected");
+    assert!(!libraries_dir.join("bad.jar").exists());
+    let file_nodes = scheduler
+        .tree_view()
+        .into_iter()
+        .flat_map(|node| node.children)
+        .map(|node| (node.name, node.state))
+        .collect::<HashMap<_, _>>();
+    assert_eq!(file_nodes["good"], TaskState::Finished);
+    assert_eq!(file_nodes["bad"], TaskState::Failed);
+
+    // a valid file is kept as is, even if the server is gone
+    drop(server);
+    let offline_task = download_libraries_task(
+        Client::new(),
+        vec![LibraryDownload {
+            url: "http://127.0.0.1:9/good.jar".to_string(),
+            ..library("good", "good.jar", good)
+        }],
+    );
+    scheduler.run(offline_task).await.unwrap();
+}
@@ -4,6 +4,18 @@ use crate::game_resolver::models::Library;
 use crate::utils::abs_path_buf::AbsPathBuf;
 
 impl Library {
+    /// A library known only by its maven coordinate.
+    pub fn from_coordinate(coordinate: &str) -> Self {
+        Library {
+            name: coordinate.to_string(),
+            downloads: None,
+            natives: None,
+            rules: None,
+            extract: None,
+            url: None,
+        }
+    }
+
     pub fn to_classpath_entry(
         &self,
         libraries_dir: AbsPathBuf,
@@ -21,7 +33,17 @@ impl Library {
     }
 
     pub fn get_jar_path(&self, libraries_dir: AbsPathBuf) -> Option<AbsPathBuf> {
-        let parts: Vec<&str> = self.name.split(':').collect();
+        let mut path = libraries_dir;
+        for segment in self.get_maven_path()?.split('/') {
+            path.push(segment);
+        }
+        Some(path)
+    }
+
+    /// `group:artifact:version[:classifier][@extension]` as a repository relative path.
+    pub fn get_maven_path(&self) -> Option<String> {
+        let (coordinate, extension) = self.name.split_once('@').unwrap_or((&self.name, "jar"));
+        let parts: Vec<&str> = coordinate.split(':').collect();
         if parts.len() < 3 {
             return None;
         }
@@ -31,22 +53,15 @@ impl Library {
         let version = parts[2];
         let classifier = parts.get(3);
 
-        let mut path = libraries_dir;
-        for segment in group_id.split('.') {
-            path.push(segment);
-        }
-
-        path.push(artifact_id);
-        path.push(version);
-
         let file_name = if let Some(c) = classifier {
-            format!("{artifact_id}-{version}-{c}.jar")
+            format!("{artifact_id}-{version}-{c}.{extension}")
         } else {
-            format!("{artifact_id}-{version}.jar")
+            format!("{artifact_id}-{version}.{extension}")
         };
 
-        path.push(file_name);
-
-        Some(path)
+        Some(format!(
+            "{}/{artifact_id}/{version}/{file_name}",
+            group_id.replace('.', "/")
+        ))
     }
 }
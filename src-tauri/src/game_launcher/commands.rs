@@ -0,0 +1,96 @@
+use crate::auth::models::PlayerProfile;
+use crate::game_installer::catalog::VersionCatalogClient;
+use crate::game_launcher::crash::CrashDiagnosis;
+use crate::game_launcher::events::emit_game_process_event;
+use crate::game_launcher::launch::{LaunchOptions, launch_instance_task};
+use crate::game_launcher::log_parser::GameLogRecord;
+use crate::game_launcher::models::{CustomInfo, LaunchHandle, LaunchSettings};
+use crate::game_launcher::process::{GameProcessInfo, GameProcessRegistry};
+use crate::scheduler::{Scheduler, Task};
+use crate::settings::modules::download::DownloadSettings;
+use crate::settings::modules::java::JavaSettings;
+use crate::settings::store::SettingsStore;
+use crate::utils::abs_path_buf::AbsPathBuf;
+use crate::utils::command::CommandResult;
+use anyhow::Result;
+use macros::command;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{App, Manager, State};
+use uuid::Uuid;
+
+pub fn setup_game_launcher(app: &mut App) -> Result<()> {
+    let registry = GameProcessRegistry::new(Arc::new(emit_game_process_event));
+    app.manage(Arc::new(registry));
+    Ok(())
+}
+
+// only offline profiles for now, accounts are not stored yet so a signed in player can't be picked
+#[command]
+pub async fn launch_instance(
+    catalog: State<'_, Arc<VersionCatalogClient>>,
+    scheduler: State<'_, Scheduler>,
+    store: State<'_, Arc<SettingsStore>>,
+    processes: State<'_, Arc<GameProcessRegistry>>,
+    minecraft_dir: String,
+    version_id: String,
+    offline_player_name: String,
+    java_path: Option<String>,
+    settings: Option<LaunchSettings>,
+) -> CommandResult<LaunchHandle> {
+    let instance_id = Uuid::new_v4();
+    let java_settings = store.get::<JavaSettings>();
+    let options = LaunchOptions {
+        instance_id,
+        minecraft_dir: AbsPathBuf::new(&minecraft_dir)?,
+        version_id: version_id.clone(),
+        player_profile: PlayerProfile::of_offline(offline_player_name),
+        java_path: java_path.map(PathBuf::from),
+        java_scan_options: java_settings.scan_options(),
+        java_vendor_preference: java_settings.vendor_preference,
+        custom_info: CustomInfo::try_from(settings.unwrap_or_default())?,
+        catalog: catalog.inner().clone(),
+        assets_base_url: store.get::<DownloadSettings>().assets_base_url,
+        processes: processes.inner().clone(),
+    };
+    let task = launch_instance_task(options);
+    let handle = LaunchHandle {
+        task_id: task.id(),
+        instance_id,
+        version_id,
+    };
+    // failures are reported through the task tree
+    drop(scheduler.spawn(task));
+    Ok(handle)
+}
+
+#[command]
+pub async fn list_running_games(
+    processes: State<'_, Arc<GameProcessRegistry>>,
+) -> CommandResult<Vec<GameProcessInfo>> {
+    Ok(processes.list_running())
+}
+
+#[command]
+pub async fn get_game_logs(
+    processes: State<'_, Arc<GameProcessRegistry>>,
+    instance_id: Uuid,
+) -> CommandResult<Vec<GameLogRecord>> {
+    Ok(processes.logs(instance_id))
+}
+
+#[command]
+pub async fn kill_game(
+    processes: State<'_, Arc<GameProcessRegistry>>,
+    instance_id: Uuid,
+) -> CommandResult<()> {
+    Ok(processes.kill(instance_id)?)
+}
+
+#[command]
+pub async fn get_crash_diagnosis(
+    processes: State<'_, Arc<GameProcessRegistry>>,
+    instance_id: Uuid,
+) -> CommandResult<Option<CrashDiagnosis>> {
+    Ok(processes.diagnosis(instance_id))
+}
@@ -0,0 +1,523 @@
+use crate::game_launcher::models::LaunchRequest;
+use crate::i18n::locales::{I18nKeys, t_args};
+use crate::utils::abs_path_buf::AbsPathBuf;
+use fluent_templates::fluent_bundle::FluentValue;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::SystemTime;
+use tokio::fs;
+
+const CRASH_REPORTS_DIR_NAME: &str = "crash-reports";
+const JVM_CRASH_LOG_PREFIX: &str = "hs_err_pid";
+const LOG_TAIL_LINES: usize = 200;
+// class file version 52 is java 8
+const CLASS_FILE_VERSION_OFFSET: u32 = 44;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CrashContext {
+    pub game_dir: AbsPathBuf,
+    pub java_major_version: u32,
+    pub required_java_major_version: u32,
+    pub max_memory_mb: Option<u64>,
+}
+
+impl CrashContext {
+    pub fn new(request: &LaunchRequest, jvm_args: &[String]) -> Self {
+        CrashContext {
+            game_dir: request.get_game_dir(),
+            java_major_version: request.java_profile.major_version,
+            required_java_major_version: request.manifest.java_version.major_version,
+            max_memory_mb: parse_max_memory_mb(jvm_args),
+        }
+    }
+}
+
+/// Reads the `-Xmx` limit in megabytes, e.g. `-Xmx4G` gives 4096.
+pub fn parse_max_memory_mb(jvm_args: &[String]) -> Option<u64> {
+    let value = jvm_args
+        .iter()
+        .rev()
+        .find_map(|arg| arg.strip_prefix("-Xmx"))?;
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount.parse().ok()?;
+    match unit.to_ascii_lowercase().as_str() {
+        "" => Some(amount / 1024 / 1024),
+        "k" => Some(amount / 1024),
+        "m" => Some(amount),
+        "g" => Some(amount * 1024),
+        "t" => Some(amount * 1024 * 1024),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Hash, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum CrashCause {
+    JavaVersionMismatch,
+    MissingModDependency,
+    MixinFailure,
+    OpenGlDriver,
+    OutOfMemory,
+    DuplicateMods,
+    InvalidJvmArguments,
+    JvmCrash,
+    Unknown,
+}
+
+impl CrashCause {
+    pub fn i18n_key(&self) -> I18nKeys {
+        match self {
+            CrashCause::JavaVersionMismatch => I18nKeys::CrashCauseJavaVersionMismatch,
+            CrashCause::MissingModDependency => I18nKeys::CrashCauseMissingModDependency,
+            CrashCause::MixinFailure => I18nKeys::CrashCauseMixinFailure,
+            CrashCause::OpenGlDriver => I18nKeys::CrashCauseOpenGlDriver,
+            CrashCause::OutOfMemory => I18nKeys::CrashCauseOutOfMemory,
+            CrashCause::DuplicateMods => I18nKeys::CrashCauseDuplicateMods,
+            CrashCause::InvalidJvmArguments => I18nKeys::CrashCauseInvalidJvmArguments,
+            CrashCause::JvmCrash => I18nKeys::CrashCauseJvmCrash,
+            CrashCause::Unknown => I18nKeys::CrashCauseUnknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashCauseReport {
+    pub cause: CrashCause,
+    pub detail: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashDiagnosis {
+    pub exit_code: Option<i32>,
+    pub causes: Vec<CrashCauseReport>,
+    pub crash_report_path: Option<String>,
+    pub jvm_crash_log_path: Option<String>,
+    pub log_tail: Vec<String>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CrashFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct CrashEvidence {
+    pub log_tail: Vec<String>,
+    pub crash_report: Option<CrashFile>,
+    pub jvm_crash_log: Option<CrashFile>,
+}
+
+// the first capture group, if any, becomes the detail of the cause
+const CRASH_RULES: &[(CrashCause, &str)] = &[
+    (
+        CrashCause::JavaVersionMismatch,
+        r"UnsupportedClassVersionError.*class file version (\d+)",
+    ),
+    (
+        CrashCause::JavaVersionMismatch,
+        r"has been compiled by a more recent version of the Java Runtime",
+    ),
+    (
+        CrashCause::MissingModDependency,
+        r"requires (?:any version|version \S+) of (?:mod )?'?([^',\n(]+?)'?(?: \([^)]*\))?, which is missing",
+    ),
+    (
+        CrashCause::MissingModDependency,
+        r"Missing or unsupported mandatory dependencies:\s*Mod ID: '([^']+)'",
+    ),
+    (CrashCause::MissingModDependency, r"Missing Mods:\s*(\S+)"),
+    (
+        CrashCause::MixinFailure,
+        r"Mixin apply for mod (\S+) failed",
+    ),
+    (CrashCause::MixinFailure, r"Mixin apply failed (\S+)"),
+    (
+        CrashCause::MixinFailure,
+        r"MixinTransformerError|InvalidInjectionException|MixinApplyError",
+    ),
+    (CrashCause::OpenGlDriver, r"Pixel format not accelerated"),
+    (CrashCause::OpenGlDriver, r"GLFW error 65542"),
+    (
+        CrashCause::OpenGlDriver,
+        r"The driver does not appear to support OpenGL",
+    ),
+    (
+        CrashCause::OpenGlDriver,
+        r"\b(atio6axx|atioglxx|ig\w+icd(?:32|64)|nvoglv(?:32|64))\.dll",
+    ),
+    (
+        CrashCause::OutOfMemory,
+        r"java\.lang\.OutOfMemoryError(?:: (.+))?",
+    ),
+    (
+        CrashCause::OutOfMemory,
+        r"Could not reserve enough space for|There is insufficient memory for the Java Runtime",
+    ),
+    (
+        CrashCause::DuplicateMods,
+        r"Found duplicate mods:\s*Mod ID: '([^']+)'",
+    ),
+    (
+        CrashCause::DuplicateMods,
+        r"Duplicate mod(?: ID)?:? '?([\w\-.]+)'?",
+    ),
+    (CrashCause::DuplicateMods, r"DuplicateModsFoundException"),
+    (
+        CrashCause::InvalidJvmArguments,
+        r"Unrecognized VM option '([^']+)'",
+    ),
+    (
+        CrashCause::InvalidJvmArguments,
+        r"Unrecognized option: (\S+)",
+    ),
+    (
+        CrashCause::InvalidJvmArguments,
+        r"Invalid (?:maximum|initial) heap size: (\S+)",
+    ),
+    (
+        CrashCause::JvmCrash,
+        r"A fatal error has been detected by the Java Runtime Environment",
+    ),
+];
+
+fn crash_rules() -> &'static [(CrashCause, Regex)] {
+    static RULES: LazyLock<Vec<(CrashCause, Regex)>> = LazyLock::new(|| {
+        CRASH_RULES
+            .iter()
+            .map(|(cause, pattern)| {
+                let regex = Regex::new(pattern)
+                    .expect("Internal Error: Failed to compile crash rule regex");
+                (*cause, regex)
+            })
+            .collect()
+    });
+    &RULES
+}
+
+/// Matches the collected output against the known crash causes, in rule table order.
+pub fn analyze_crash(
+    evidence: &CrashEvidence,
+    context: &CrashContext,
+    exit_code: Option<i32>,
+) -> CrashDiagnosis {
+    let log = evidence.log_tail.join("\n");
+    let sources = [
+        Some(log.as_str()),
+        evidence.crash_report.as_ref().map(|f| f.content.as_str()),
+        evidence.jvm_crash_log.as_ref().map(|f| f.content.as_str()),
+    ];
+
+    let mut found: Vec<(CrashCause, Option<String>)> = vec![];
+    for (cause, regex) in crash_rules() {
+        if found.iter().any(|(c, _)| c == cause) {
+            continue;
+        }
+        let captures = sources.iter().flatten().find_map(|s| regex.captures(s));
+        if let Some(captures) = captures {
+            let detail = captures.get(1).map(|m| m.as_str().trim().to_string());
+            found.push((*cause, detail));
+        }
+    }
+
+    let required_java = required_java_major(&found, context);
+    if context.java_major_version < required_java
+        && !found
+            .iter()
+            .any(|(c, _)| *c == CrashCause::JavaVersionMismatch)
+    {
+        found.insert(0, (CrashCause::JavaVersionMismatch, None));
+    }
+    if evidence.jvm_crash_log.is_some() && !found.iter().any(|(c, _)| *c == CrashCause::JvmCrash) {
+        found.push((CrashCause::JvmCrash, None));
+    }
+    if found.is_empty() {
+        found.push((CrashCause::Unknown, None));
+    }
+
+    let crash_file_path =
+        |file: &Option<CrashFile>| file.as_ref().map(|f| f.path.to_string_lossy().to_string());
+    let jvm_crash_log_path = crash_file_path(&evidence.jvm_crash_log);
+    let causes = found
+        .into_iter()
+        .map(|(cause, detail)| {
+            let args = crash_i18n_args(
+                detail.as_deref(),
+                context,
+                required_java,
+                exit_code,
+                jvm_crash_log_path.as_deref(),
+            );
+            CrashCauseReport {
+                cause,
+                message: t_args(cause.i18n_key(), &args),
+                detail,
+            }
+        })
+        .collect();
+
+    CrashDiagnosis {
+        exit_code,
+        causes,
+        crash_report_path: crash_file_path(&evidence.crash_report),
+        jvm_crash_log_path,
+        log_tail: evidence.log_tail.clone(),
+    }
+}
+
+fn required_java_major(found: &[(CrashCause, Option<String>)], context: &CrashContext) -> u32 {
+    let from_class_version = found
+        .iter()
+        .find(|(c, _)| *c == CrashCause::JavaVersionMismatch)
+        .and_then(|(_, detail)| detail.as_ref()?.parse::<u32>().ok())
+        .map(|version| version.saturating_sub(CLASS_FILE_VERSION_OFFSET));
+    from_class_version
+        .unwrap_or_default()
+        .max(context.required_java_major_version)
+}
+
+fn crash_i18n_args(
+    detail: Option<&str>,
+    context: &CrashContext,
+    required_java: u32,
+    exit_code: Option<i32>,
+    jvm_crash_log_path: Option<&str>,
+) -> HashMap<Cow<'static, str>, FluentValue<'static>> {
+    let or_none = |value: Option<String>| value.unwrap_or_else(|| "none".to_string());
+    let args: Vec<(&'static str, FluentValue<'static>)> = vec![
+        ("detail", or_none(detail.map(str::to_string)).into()),
+        ("current", context.java_major_version.into()),
+        ("required", required_java.into()),
+        (
+            "memory",
+            or_none(context.max_memory_mb.map(|m| m.to_string())).into(),
+        ),
+        ("code", or_none(exit_code.map(|c| c.to_string())).into()),
+        (
+            "path",
+            or_none(jvm_crash_log_path.map(str::to_string)).into(),
+        ),
+    ];
+    args.into_iter()
+        .map(|(k, v)| (Cow::Borrowed(k), v))
+        .collect()
+}
+
+/// Gathers the log tail and the crash files written since the game started.
+pub async fn collect_crash_evidence(
+    context: &CrashContext,
+    logs: &[String],
+    started_at: SystemTime,
+) -> CrashEvidence {
+    let log_tail = logs[logs.len().saturating_sub(LOG_TAIL_LINES)..].to_vec();
+    let crash_reports_dir = context.game_dir.join(CRASH_REPORTS_DIR_NAME);
+    let crash_report = newest_file(&crash_reports_dir, started_at, |name| {
+        name.ends_with(".txt")
+    })
+    .await;
+    let jvm_crash_log = newest_file(&context.game_dir, started_at, |name| {
+        name.starts_with(JVM_CRASH_LOG_PREFIX) && name.ends_with(".log")
+    })
+    .await;
+    CrashEvidence {
+        log_tail,
+        crash_report: read_crash_file(crash_report).await,
+        jvm_crash_log: read_crash_file(jvm_crash_log).await,
+    }
+}
+
+async fn newest_file(
+    dir: &Path,
+    since: SystemTime,
+    filter: impl Fn(&str) -> bool,
+) -> Option<PathBuf> {
+    let mut entries = fs::read_dir(dir).await.ok()?;
+    let mut newest: Option<(SystemTime, PathBuf)> = None;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if !entry.file_name().to_str().is_some_and(&filter) {
+            continue;
+        }
+        let Ok(modified) = entry.metadata().await.and_then(|m| m.modified()) else {
+            continue;
+        };
+        if modified >= since && newest.as_ref().is_none_or(|(time, _)| modified > *time) {
+            newest = Some((modified, entry.path()));
+        }
+    }
+    newest.map(|(_, path)| path)
+}
+
+async fn read_crash_file(path: Option<PathBuf>) -> Option<CrashFile> {
+    let path = path?;
+    let bytes = fs::read(&path).await.ok()?;
+    Some(CrashFile {
+        content: String::from_utf8_lossy(&bytes).to_string(),
+        path,
+    })
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn context(java: u32, required: u32) -> CrashContext {
+        CrashContext {
+            game_dir: AbsPathBuf::new(&std::env::temp_dir()).unwrap(),
+            java_major_version: java,
+            required_java_major_version: required,
+            max_memory_mb: Some(2048),
+        }
+    }
+
+    fn log(lines: &[&str]) -> CrashEvidence {
+        CrashEvidence {
+            log_tail: lines.iter().map(|l| l.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn causes(diagnosis: &CrashDiagnosis) -> Vec<CrashCause> {
+        diagnosis.causes.iter().map(|c| c.cause).collect()
+    }
+
+    #[test]
+    fn test_parse_max_memory() {
+        let args = |arg: &str| vec!["-Xms512M".to_string(), arg.to_string()];
+
+        assert_eq!(parse_max_memory_mb(&args("-Xmx4G")), Some(4096));
+        assert_eq!(parse_max_memory_mb(&args("-Xmx2048m")), Some(2048));
+        assert_eq!(parse_max_memory_mb(&args("-Xmx1048576k")), Some(1024));
+        assert_eq!(parse_max_memory_mb(&args("-Dfoo=bar")), None);
+    }
+
+    #[test]
+    fn test_java_version_mismatch_from_class_version() {
+        let evidence = log(&[
+            "Exception in thread \"main\" java.lang.UnsupportedClassVersionError: net/minecraft/client/main/Main has been compiled by a more recent version of the Java Runtime (class file version 65.0), this version of the Java Runtime only recognizes class file versions up to 52.0",
+        ]);
+
+        let diagnosis = analyze_crash(&evidence, &context(8, 8), Some(1));
+
+        assert_eq!(causes(&diagnosis), [CrashCause::JavaVersionMismatch]);
+        let message = &diagnosis.causes[0].message;
+        assert!(
+            message.contains("21"),
+            "Required java should be shown: {message}"
+        );
+    }
+
+    #[test]
+    fn test_java_version_mismatch_from_context() {
+        let diagnosis = analyze_crash(&log(&["Error: LinkageError"]), &context(8, 17), Some(1));
+
+        assert_eq!(causes(&diagnosis), [CrashCause::JavaVersionMismatch]);
+    }
+
+    #[test]
+    fn test_missing_fabric_dependency() {
+        let evidence = log(&[
+            "net.fabricmc.loader.impl.FormattedException: Mod resolution encountered an incompatible mod set!",
+            " - Mod 'Sodium Extra' (sodium-extra) 0.5.1 requires any version of mod sodium, which is missing!",
+        ]);
+
+        let diagnosis = analyze_crash(&evidence, &context(17, 17), Some(1));
+
+        assert_eq!(causes(&diagnosis), [CrashCause::MissingModDependency]);
+        assert_eq!(diagnosis.causes[0].detail.as_deref(), Some("sodium"));
+        assert!(diagnosis.causes[0].message.contains("sodium"));
+    }
+
+    #[test]
+    fn test_crash_report_and_jvm_log_are_matched() {
+        let evidence = CrashEvidence {
+            log_tail: vec![],
+            crash_report: Some(CrashFile {
+                path: PathBuf::from("crash-reports/crash.txt"),
+                content: "Mixin apply for mod iris failed iris.mixins.json:MixinLevelRenderer"
+                    .to_string(),
+            }),
+            jvm_crash_log: Some(CrashFile {
+                path: PathBuf::from("hs_err_pid42.log"),
+                content: "# Problematic frame:\n# C  [atio6axx.dll+0x1a2b3c]".to_string(),
+            }),
+        };
+
+        let diagnosis = analyze_crash(&evidence, &context(17, 17), None);
+
+        assert_eq!(
+            causes(&diagnosis),
+            [
+                CrashCause::MixinFailure,
+                CrashCause::OpenGlDriver,
+                CrashCause::JvmCrash
+            ]
+        );
+        assert_eq!(diagnosis.causes[0].detail.as_deref(), Some("iris"));
+        assert_eq!(
+            diagnosis.jvm_crash_log_path.as_deref(),
+            Some("hs_err_pid42.log")
+        );
+    }
+
+    #[test]
+    fn test_out_of_memory_shows_limit() {
+        let evidence = log(&["java.lang.OutOfMemoryError: Java heap space"]);
+
+        let diagnosis = analyze_crash(&evidence, &context(17, 17), Some(-1));
+
+        assert_eq!(causes(&diagnosis), [CrashCause::OutOfMemory]);
+        assert!(diagnosis.causes[0].message.contains("2048"));
+    }
+
+    #[test]
+    fn test_unknown_cause() {
+        let diagnosis = analyze_crash(&log(&["Stopping!"]), &context(17, 17), Some(3));
+
+        assert_eq!(causes(&diagnosis), [CrashCause::Unknown]);
+        assert!(diagnosis.causes[0].message.contains('3'));
+    }
+
+    #[tokio::test]
+    async fn test_collect_newest_crash_files() {
+        let root = TempDir::new().unwrap();
+        let game_dir = AbsPathBuf::new(root.path()).unwrap();
+        let reports_dir = root.path().join(CRASH_REPORTS_DIR_NAME);
+        std::fs::create_dir_all(&reports_dir).unwrap();
+        let started_at = SystemTime::now() - Duration::from_secs(60);
+        std::fs::write(reports_dir.join("crash-old.txt"), "old").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(reports_dir.join("crash-old.txt"))
+            .unwrap()
+            .set_modified(started_at - Duration::from_secs(60))
+            .unwrap();
+        std::fs::write(reports_dir.join("crash-new.txt"), "new").unwrap();
+        std::fs::write(root.path().join("hs_err_pid7.log"), "fatal").unwrap();
+        let context = CrashContext {
+            game_dir,
+            ..context(17, 17)
+        };
+        let logs: Vec<String> = (0..300).map(|i| i.to_string()).collect();
+
+        let evidence = collect_crash_evidence(&context, &logs, started_at).await;
+
+        assert_eq!(evidence.log_tail.len(), LOG_TAIL_LINES);
+        assert_eq!(evidence.log_tail[0], "100");
+        assert_eq!(evidence.crash_report.unwrap().content, "new");
+        assert_eq!(evidence.jvm_crash_log.unwrap().content, "fatal");
+    }
+}
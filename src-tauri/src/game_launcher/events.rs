@@ -0,0 +1,38 @@
+use crate::game_launcher::crash::CrashDiagnosis;
+use crate::game_launcher::log_parser::GameLogRecord;
+use crate::game_launcher::process::{GameProcessEvent, GameProcessInfo};
+use anyhow::Result;
+use log::warn;
+use macros::event;
+use tauri::{Emitter, EventTarget};
+use uuid::Uuid;
+
+#[event]
+#[serde(rename_all = "camelCase")]
+pub struct GameLogEvent {
+    pub instance_id: Uuid,
+    pub record: GameLogRecord,
+}
+
+#[event]
+pub struct GameExitEvent {
+    pub info: GameProcessInfo,
+    pub diagnosis: Option<CrashDiagnosis>,
+}
+
+pub fn emit_game_process_event(event: GameProcessEvent) {
+    let result = match event {
+        GameProcessEvent::Log {
+            instance_id,
+            record,
+        } => GameLogEvent {
+            instance_id,
+            record,
+        }
+        .emit(),
+        GameProcessEvent::Exited { info, diagnosis } => GameExitEvent { info, diagnosis }.emit(),
+    };
+    if let Err(e) = result {
+        warn!("Failed to emit game process event: {e}");
+    }
+}
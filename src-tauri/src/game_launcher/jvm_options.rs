@@ -0,0 +1,319 @@
+use crate::constants::minecraft_dir::MODS_DIR_NAME;
+use crate::game_launcher::models::LaunchError;
+use crate::java_runtime::models::JavaInstance;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::Path;
+use tokio::fs;
+
+const MIN_HEAP_MB: u64 = 1024;
+// a 32-bit jvm cannot reserve much more than this in one block on every os
+const MAX_32BIT_HEAP_MB: u64 = 1024;
+// left for the os and the launcher itself
+const RESERVED_SYSTEM_MB: u64 = 1536;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum MemoryPolicy {
+    #[default]
+    Auto,
+    #[serde(rename_all = "camelCase")]
+    Fixed { max_mb: u64, min_mb: Option<u64> },
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SystemMemory {
+    pub total_mb: u64,
+    pub available_mb: u64,
+}
+
+impl SystemMemory {
+    pub async fn read() -> Option<Self> {
+        let content = fs::read_to_string("/proc/meminfo").await.ok()?;
+        parse_meminfo(&content)
+    }
+}
+
+fn parse_meminfo(content: &str) -> Option<SystemMemory> {
+    let field = |name: &str| {
+        content.lines().find_map(|line| {
+            let value = line.strip_prefix(name)?.strip_prefix(':')?;
+            value.split_whitespace().next()?.parse::<u64>().ok()
+        })
+    };
+    // MemAvailable only exists since linux 3.14
+    let available_kb =
+        field("MemAvailable").or_else(|| Some(field("MemFree")? + field("Cached").unwrap_or(0)))?;
+    Some(SystemMemory {
+        total_mb: field("MemTotal")? / 1024,
+        available_mb: available_kb / 1024,
+    })
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct MemoryAllocation {
+    pub max_mb: u64,
+    pub min_mb: Option<u64>,
+}
+
+impl MemoryAllocation {
+    pub fn to_jvm_args(self) -> Vec<String> {
+        let mut args = vec![format!("-Xmx{}m", self.max_mb)];
+        args.extend(self.min_mb.map(|min_mb| format!("-Xms{min_mb}m")));
+        args
+    }
+}
+
+impl MemoryPolicy {
+    pub fn allocate(
+        &self,
+        system: Option<SystemMemory>,
+        mod_count: usize,
+        is_64bit_java: bool,
+    ) -> MemoryAllocation {
+        let heap_limit = if is_64bit_java {
+            u64::MAX
+        } else {
+            MAX_32BIT_HEAP_MB
+        };
+        match self {
+            MemoryPolicy::Fixed { max_mb, min_mb } => {
+                if *max_mb > heap_limit {
+                    warn!("-Xmx{max_mb}m is too large for a 32-bit java, using {heap_limit}m");
+                }
+                if *max_mb < MIN_HEAP_MB {
+                    warn!("-Xmx{max_mb}m is too small to start the game, using {MIN_HEAP_MB}m");
+                }
+                let max_mb = (*max_mb).min(heap_limit).max(MIN_HEAP_MB);
+                if let Some(min_mb) = min_mb
+                    && *min_mb > max_mb
+                {
+                    warn!("-Xms{min_mb}m is larger than the heap limit, using {max_mb}m");
+                }
+                MemoryAllocation {
+                    max_mb,
+                    min_mb: min_mb
+                        .filter(|min_mb| *min_mb > 0)
+                        .map(|min_mb| min_mb.min(max_mb)),
+                }
+            }
+            MemoryPolicy::Auto => {
+                let wanted = recommended_heap_mb(mod_count);
+                let usable = system.map_or(wanted, |system| {
+                    let spare = system.total_mb.saturating_sub(RESERVED_SYSTEM_MB);
+                    spare.min(system.available_mb)
+                });
+                MemoryAllocation {
+                    max_mb: wanted.min(usable).min(heap_limit).max(MIN_HEAP_MB),
+                    min_mb: None,
+                }
+            }
+        }
+    }
+}
+
+fn recommended_heap_mb(mod_count: usize) -> u64 {
+    match mod_count {
+        0 => 2048,
+        1..=50 => 4096,
+        51..=150 => 6144,
+        _ => 8192,
+    }
+}
+
+pub async fn count_mods(game_dir: &Path) -> usize {
+    let mut count = 0;
+    if let Ok(mut entries) = fs::read_dir(game_dir.join(MODS_DIR_NAME)).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if entry.file_name().to_string_lossy().ends_with(".jar") {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Hash, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum JvmFlagPreset {
+    G1Tuned,
+    ZgcGenerational,
+    LowMemory,
+}
+
+impl JvmFlagPreset {
+    pub fn min_java_major_version(&self) -> u32 {
+        match self {
+            JvmFlagPreset::G1Tuned | JvmFlagPreset::LowMemory => 8,
+            JvmFlagPreset::ZgcGenerational => 21,
+        }
+    }
+
+    fn flags(&self, java_major_version: u32) -> Vec<&'static str> {
+        match self {
+            JvmFlagPreset::G1Tuned => vec![
+                "-XX:+UseG1GC",
+                "-XX:+ParallelRefProcEnabled",
+                "-XX:MaxGCPauseMillis=200",
+                "-XX:+UnlockExperimentalVMOptions",
+                "-XX:+DisableExplicitGC",
+                "-XX:G1NewSizePercent=30",
+                "-XX:G1MaxNewSizePercent=40",
+                "-XX:G1HeapRegionSize=8M",
+                "-XX:G1ReservePercent=20",
+                "-XX:G1HeapWastePercent=5",
+                "-XX:G1MixedGCCountTarget=4",
+                "-XX:InitiatingHeapOccupancyPercent=15",
+                "-XX:G1MixedGCLiveThresholdPercent=90",
+                "-XX:SurvivorRatio=32",
+                "-XX:+PerfDisableSharedMem",
+                "-XX:MaxTenuringThreshold=1",
+            ],
+            // generational mode is the only one since java 23, and the flag is gone in 24
+            JvmFlagPreset::ZgcGenerational if java_major_version >= 23 => vec!["-XX:+UseZGC"],
+            JvmFlagPreset::ZgcGenerational => vec!["-XX:+UseZGC", "-XX:+ZGenerational"],
+            JvmFlagPreset::LowMemory => vec![
+                "-XX:+UseSerialGC",
+                "-XX:MinHeapFreeRatio=10",
+                "-XX:MaxHeapFreeRatio=20",
+                "-XX:ReservedCodeCacheSize=64m",
+            ],
+        }
+    }
+
+    pub fn jvm_args(&self, java: &JavaInstance) -> Result<Vec<String>, LaunchError> {
+        let required = self.min_java_major_version();
+        if java.major_version < required {
+            return Err(LaunchError::UnsupportedJvmPreset {
+                preset: *self,
+                required,
+                actual: java.major_version,
+            });
+        }
+        Ok(self
+            .flags(java.major_version)
+            .into_iter()
+            .map(String::from)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::java_runtime::models::{JavaArch, JavaSource, JavaVersionParts};
+    use std::path::PathBuf;
+
+    const MEMINFO: &str = "MemTotal:       16303908 kB\nMemFree:         1210344 kB\nMemAvailable:    9175712 kB\nBuffers:          402148 kB\nCached:          7203836 kB\n";
+
+    fn system(total_mb: u64, available_mb: u64) -> Option<SystemMemory> {
+        Some(SystemMemory {
+            total_mb,
+            available_mb,
+        })
+    }
+
+    #[test]
+    fn test_parse_meminfo() {
+        assert_eq!(parse_meminfo(MEMINFO), system(15921, 8960));
+        assert_eq!(
+            parse_meminfo("MemTotal: 2097152 kB\nMemFree: 524288 kB\nCached: 524288 kB\n"),
+            system(2048, 1024)
+        );
+        assert_eq!(parse_meminfo("garbage"), None);
+    }
+
+    #[test]
+    fn test_auto_memory_grows_with_mods_within_system_limits() {
+        let auto = |system, mods, is_64bit| MemoryPolicy::Auto.allocate(system, mods, is_64bit);
+
+        assert_eq!(auto(system(16384, 8192), 0, true).max_mb, 2048);
+        assert_eq!(auto(system(16384, 8192), 200, true).max_mb, 8192);
+        assert_eq!(auto(system(8192, 6000), 200, true).max_mb, 6000);
+        assert_eq!(auto(system(4096, 3000), 100, true).max_mb, 2560);
+        assert_eq!(auto(system(2048, 256), 100, true).max_mb, MIN_HEAP_MB);
+        assert_eq!(auto(system(16384, 8192), 100, false).max_mb, 1024);
+        assert_eq!(auto(None, 30, true).max_mb, 4096);
+    }
+
+    #[test]
+    fn test_fixed_memory_keeps_a_usable_heap() {
+        let tiny = MemoryPolicy::Fixed {
+            max_mb: 256,
+            min_mb: Some(512),
+        };
+        let inverted = MemoryPolicy::Fixed {
+            max_mb: 2048,
+            min_mb: Some(4096),
+        };
+        let zero = MemoryPolicy::Fixed {
+            max_mb: 0,
+            min_mb: Some(0),
+        };
+
+        assert_eq!(
+            tiny.allocate(None, 0, true).to_jvm_args(),
+            vec!["-Xmx1024m", "-Xms512m"]
+        );
+        assert_eq!(
+            inverted.allocate(None, 0, true).to_jvm_args(),
+            vec!["-Xmx2048m", "-Xms2048m"]
+        );
+        assert_eq!(
+            zero.allocate(None, 0, true).to_jvm_args(),
+            vec!["-Xmx1024m"]
+        );
+    }
+
+    #[test]
+    fn test_fixed_memory_is_clamped_for_32_bit_java() {
+        let fixed = MemoryPolicy::Fixed {
+            max_mb: 4096,
+            min_mb: Some(2048),
+        };
+
+        assert_eq!(
+            fixed.allocate(None, 0, true).to_jvm_args(),
+            vec!["-Xmx4096m", "-Xms2048m"]
+        );
+        assert_eq!(
+            fixed.allocate(None, 0, false).to_jvm_args(),
+            vec!["-Xmx1024m", "-Xms1024m"]
+        );
+    }
+
+    #[test]
+    fn test_presets_check_the_java_version() {
+        let java = |major_version: u32| JavaInstance {
+            java_home: PathBuf::from("/usr/lib/jvm/java"),
+            executable_path: PathBuf::from("/usr/lib/jvm/java/bin/java"),
+            version: major_version.to_string(),
+            version_parts: JavaVersionParts::default(),
+            major_version,
+            arch: JavaArch::X64,
+            is_64bit: true,
+            vendor_name: "Eclipse Temurin".to_string(),
+            is_jdk: false,
+            has_javaw: false,
+            source: JavaSource::System,
+        };
+
+        assert!(matches!(
+            JvmFlagPreset::ZgcGenerational.jvm_args(&java(17)),
+            Err(LaunchError::UnsupportedJvmPreset { required: 21, .. })
+        ));
+        assert!(
+            JvmFlagPreset::ZgcGenerational
+                .jvm_args(&java(21))
+                .unwrap()
+                .contains(&"-XX:+ZGenerational".to_string())
+        );
+        assert_eq!(
+            JvmFlagPreset::ZgcGenerational.jvm_args(&java(25)).unwrap(),
+            vec!["-XX:+UseZGC"]
+        );
+        assert!(JvmFlagPreset::G1Tuned.jvm_args(&java(8)).is_ok());
+    }
+}
@@ -1,19 +1,341 @@
-use crate::game_launcher::models::LaunchRequest;
-use crate::scheduler::{Task, task};
+use crate::auth::models::PlayerProfile;
+use crate::constants::minecraft_dir::ASSETS_DIR_NAME;
+use crate::game_assets::downloader::{
+    collect_asset_objects, download_asset_index, download_assets_task,
+};
+use crate::game_assets::layout::materialize_asset_layout;
+use crate::game_assets::log_config::download_log_config_task;
+use crate::game_assets::models::AssetIndexContent;
+use crate::game_installer::catalog::VersionCatalogClient;
+use crate::game_installer::download::{download_file, is_file_intact};
+use crate::game_installer::libraries::{collect_library_downloads, download_libraries_task};
+use crate::game_launcher::crash::CrashContext;
+use crate::game_launcher::jvm_options::{SystemMemory, count_mods};
+use crate::game_launcher::models::LaunchError::{IncompatibleJava, InvalidJavaRuntime};
+use crate::game_launcher::models::{CustomInfo, LaunchRequest, LaunchVersionManifest, RuleContext};
+use crate::game_launcher::natives::extract_natives_task;
+use crate::game_launcher::process::{GameProcessInfo, GameProcessRegistry};
+use crate::game_resolver::models::VersionData::{Broken, Normal};
+use crate::game_resolver::models::{DownloadFile, MinecraftFolderInfo};
+use crate::game_resolver::resolve::resolve_minecraft_folder;
+use crate::java_runtime::inspector::inspect_java_executable;
+use crate::java_runtime::models::JavaInstance;
+use crate::java_runtime::scanner::{JavaScanOptions, scan_all};
+use crate::java_runtime::selector::{JavaRequirement, select_java};
+use crate::scheduler::builder::TaskBuilder;
+use crate::scheduler::{Context, Task, parallel, pipeline, task, task_with_ctx};
+use crate::utils::abs_path_buf::AbsPathBuf;
 use crate::utils::executor::Executable;
-use anyhow::Result;
+use anyhow::{Result, anyhow, bail};
+use log::info;
+use reqwest::Client;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::fs;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
 
-pub fn get_launch_task() -> impl Task {
-    task("launch_minecraft", launch_minecraft)
+#[derive(Clone)]
+pub struct LaunchOptions {
+    pub instance_id: Uuid,
+    pub minecraft_dir: AbsPathBuf,
+    pub version_id: String,
+    pub player_profile: PlayerProfile,
+    pub java_path: Option<PathBuf>,
+    pub java_vendor_preference: Vec<String>,
+    pub java_scan_options: JavaScanOptions,
+    pub custom_info: CustomInfo,
+    pub catalog: Arc<VersionCatalogClient>,
+    pub assets_base_url: String,
+    pub processes: Arc<GameProcessRegistry>,
 }
 
-async fn launch_minecraft(request: LaunchRequest) -> Result<()> {
-    let executable = get_launch_executable(request).await?;
-    let process = executable.spawn()?;
-    // TODO
+type ResolvedVersion = (MinecraftFolderInfo, LaunchVersionManifest);
+
+// resolve -> choose java -> asset index -> client jar, libraries, assets -> natives, log config -> spawn
+pub fn launch_instance_task(options: LaunchOptions) -> impl Task<Input = (), Output = ()> {
+    let (resolve_options, request_options, files_options) =
+        (options.clone(), options.clone(), options.clone());
+    let (java_path, vendor_preference, scan_options) = (
+        options.java_path.clone(),
+        options.java_vendor_preference.clone(),
+        options.java_scan_options.clone(),
+    );
+    let processes = options.processes.clone();
+    pipeline(format!("Launch {}", options.version_id))
+        .first(task("Resolve version", move |_: ()| {
+            resolve_version(resolve_options.clone())
+        }))
+        .then(task("Choose java", move |resolved: ResolvedVersion| {
+            choose_java(
+                java_path.clone(),
+                vendor_preference.clone(),
+                scan_options.clone(),
+                resolved,
+            )
+        }))
+        .then(task("Download asset index", move |chosen| {
+            prepare_launch_request(request_options.clone(), chosen)
+        }))
+        // the files to download are only known at this point, the wrapper builds the stage and
+        // runs it in place, so the stage itself is what shows up in the view
+        .then(
+            task_with_ctx("Prepare game files", move |prepared, ctx| {
+                download_game_files(files_options.clone(), prepared, ctx)
+            })
+            .critical()
+            .hidden_in_view(),
+        )
+        .then(
+            task_with_ctx("Start game", move |request: LaunchRequest, ctx| {
+                let (processes, catalog) = (processes.clone(), options.catalog.clone());
+                async move {
+                    let natives_dir = request.get_natives_dir();
+                    let result = get_launch_task(request, processes, catalog)
+                        .run((), ctx)
+                        .await;
+                    if result.is_err() {
+                        let _ = fs::remove_dir_all(&natives_dir).await;
+                    }
+                    result
+                }
+            })
+            .critical()
+            .hidden_in_view(),
+        )
+        .build()
+}
+
+async fn resolve_version(options: LaunchOptions) -> Result<ResolvedVersion> {
+    let mut folder = resolve_minecraft_folder(options.minecraft_dir).await;
+    let manifest = folder
+        .version_info
+        .iter()
+        .find_map(|version| match version {
+            Normal(version) if version.manifest.id == options.version_id => {
+                Some(Ok(version.manifest.clone()))
+            }
+            Broken(broken) if broken.id == options.version_id => {
+                Some(Err(anyhow!(broken.reason.clone())))
+            }
+            _ => None,
+        })
+        .unwrap_or_else(|| Err(anyhow!("Version '{}' is not installed", options.version_id)))?;
+    folder.version_info.clear();
+    Ok((folder, LaunchVersionManifest::try_from(manifest)?))
+}
+
+async fn choose_java(
+    java_path: Option<PathBuf>,
+    vendor_preference: Vec<String>,
+    mut scan_options: JavaScanOptions,
+    (folder, manifest): ResolvedVersion,
+) -> Result<(MinecraftFolderInfo, LaunchVersionManifest, JavaInstance)> {
+    let rule_context = RuleContext {
+        os_info: os_info::get(),
+        user_features: Default::default(),
+    };
+    let requirement = JavaRequirement::new(&manifest.java_version, &rule_context);
+    let java = match java_path {
+        Some(path) => {
+            let java = inspect_java_executable(path)
+                .await
+                .ok_or(InvalidJavaRuntime)?;
+            if !requirement.is_met_by(&java) {
+                return Err(IncompatibleJava {
+                    required: requirement.major_version,
+                    actual: java.major_version,
+                    arch: java.arch,
+                }
+                .into());
+            }
+            java
+        }
+        None => {
+            scan_options.minecraft_dirs.push(folder.path.to_path_buf());
+            select_java(
+                scan_all(&scan_options).await,
+                &requirement,
+                &vendor_preference,
+            )?
+        }
+    };
+    info!(
+        "Using java {} ({}) for {}",
+        java.version,
+        java.executable_path.display(),
+        manifest.id
+    );
+    Ok((folder, manifest, java))
+}
+
+type PreparedLaunch = (LaunchRequest, AssetIndexContent);
+
+async fn prepare_launch_request(
+    options: LaunchOptions,
+    (folder, manifest, java): (MinecraftFolderInfo, LaunchVersionManifest, JavaInstance),
+) -> Result<PreparedLaunch> {
+    let assets_dir = folder.path.join(ASSETS_DIR_NAME);
+    let index_url = options.catalog.resolve_url(&manifest.asset_index.url);
+    let index = download_asset_index(
+        &Client::new(),
+        &index_url,
+        &manifest.asset_index,
+        &assets_dir,
+    )
+    .await?;
+    let request = LaunchRequest::new(
+        options.instance_id,
+        folder,
+        manifest,
+        java,
+        options.custom_info,
+        options.player_profile,
+        index.layout(),
+    )?;
+    Ok((request, index))
+}
+
+async fn download_game_files(
+    options: LaunchOptions,
+    (request, index): PreparedLaunch,
+    ctx: Context,
+) -> Result<LaunchRequest> {
+    let client = Client::new();
+    let assets_dir = request.get_assets_dir();
+    let libraries = collect_library_downloads(
+        &request.manifest.libraries,
+        &request.get_libraries_dir(),
+        &request.get_rule_context(),
+    );
+    let objects = collect_asset_objects(&index, &assets_dir, &options.assets_base_url);
+    let client_file = request.manifest.downloads.clone().map(|d| d.client);
+    let client_weight = client_file.as_ref().map_or(1, |f| (f.size / 1024).max(1));
+    let client_path = request.get_game_jar_path();
+
+    parallel("Prepare game files")
+        .add(
+            task("Download client jar", move |_: ()| {
+                download_client_jar(
+                    client.clone(),
+                    options.catalog.clone(),
+                    client_file.clone(),
+                    client_path.clone(),
+                )
+            })
+            .with_weight(client_weight),
+        )
+        .add(discard_outputs(download_libraries_task(
+            Client::new(),
+            libraries,
+        )))
+        .add(discard_outputs(download_assets_task(
+            Client::new(),
+            objects,
+        )))
+        .build()
+        .run((), ctx)
+        .await?;
+
+    let asset_index = &request.manifest.asset_index;
+    materialize_asset_layout(
+        &index,
+        &assets_dir,
+        &asset_index.id,
+        &request.get_game_dir(),
+    )
+    .await?;
+    Ok(request)
+}
+
+fn discard_outputs<T>(stage: T) -> impl Task<Input = (), Output = ()>
+where
+    T: Task<Input = (), Output = Vec<()>>,
+{
+    TaskBuilder::new(stage)
+        .then(task("Discard outputs", |_: Vec<()>| async { Ok(()) }).hidden_in_view())
+        .build()
+}
+
+async fn download_client_jar(
+    client: Client,
+    catalog: Arc<VersionCatalogClient>,
+    client_file: Option<DownloadFile>,
+    path: AbsPathBuf,
+) -> Result<()> {
+    let Some(client_file) = client_file else {
+        if !path.is_file() {
+            bail!("Game jar {} is missing", path.display());
+        }
+        return Ok(());
+    };
+    let sha1 = Some(client_file.sha1.as_str()).filter(|sha1| !sha1.is_empty());
+    let size = Some(client_file.size).filter(|size| *size > 0);
+    if !is_file_intact(&path, sha1, size).await {
+        let url = catalog.resolve_url(&client_file.url);
+        download_file(&client, &url, &path, sha1, size).await?;
+    }
     Ok(())
 }
 
+pub fn get_launch_task(
+    request: LaunchRequest,
+    processes: Arc<GameProcessRegistry>,
+    catalog: Arc<VersionCatalogClient>,
+) -> impl Task<Input = (), Output = ()> {
+    let in_use = processes
+        .list_running()
+        .iter()
+        .map(|p| p.instance_id)
+        .chain([request.instance_id])
+        .map(|id| id.to_string())
+        .collect();
+    let natives =
+        extract_natives_task(request.get_native_jars(), request.get_natives_dir(), in_use);
+    let log_config = match request.custom_info.custom_log_config {
+        Some(_) => None,
+        None => request.manifest.logging.client.clone(),
+    };
+    let log_config =
+        download_log_config_task(Client::new(), catalog, log_config, request.get_assets_dir());
+    pipeline("Start game")
+        .first(natives)
+        .then(log_config)
+        .then(task("spawn_minecraft", move |_: ()| {
+            launch_minecraft(request.clone(), processes.clone())
+        }))
+        .build()
+}
+
+async fn launch_minecraft(
+    request: LaunchRequest,
+    processes: Arc<GameProcessRegistry>,
+) -> Result<()> {
+    let natives_dir = request.get_natives_dir();
+    let exited = spawn_minecraft(&request, &processes).await?;
+    // the natives of this launch are only needed while the game runs
+    tokio::spawn(async move {
+        let _ = exited.await;
+        let _ = fs::remove_dir_all(&natives_dir).await;
+    });
+    Ok(())
+}
+
+async fn spawn_minecraft(
+    request: &LaunchRequest,
+    processes: &Arc<GameProcessRegistry>,
+) -> Result<JoinHandle<GameProcessInfo>> {
+    fs::create_dir_all(request.get_game_dir()).await?;
+    let (instance_id, version_id) = (request.instance_id, request.manifest.id.clone());
+    let executable = get_launch_executable(request.clone()).await?;
+    let crash_context = CrashContext::new(request, &executable.args);
+    let process = executable.spawn()?;
+    info!("Minecraft {version_id} started as {instance_id}");
+
+    // the registry keeps watching the game after the launch task is done
+    Ok(processes.track(instance_id, version_id, process, crash_context))
+}
+
 async fn get_launch_executable(request: LaunchRequest) -> Result<Executable> {
     let java = request.java_profile.get_java_executable_path_str()?;
 
@@ -21,13 +343,17 @@ async fn get_launch_executable(request: LaunchRequest) -> Result<Executable> {
     let arguments_context = request.get_arguments_context()?;
     let mut args = vec![];
 
+    // custom args come last, so they can still override the memory policy and preset
+    let mut jvm_args = get_jvm_options(&request).await?;
+    jvm_args.extend(request.custom_info.custom_jvm_args.clone());
     args.append(&mut request.manifest.arguments.get_jvm_arguments(
         rule_context.clone(),
         arguments_context.clone(),
-        request.custom_info.custom_jvm_args.clone(),
+        jvm_args,
     ));
+    args.extend(request.get_logging_argument());
 
-    args.push(request.manifest.main_class);
+    args.push(request.manifest.main_class.clone());
 
     args.append(&mut request.manifest.arguments.get_game_arguments(
         rule_context,
@@ -38,7 +364,48 @@ async fn get_launch_executable(request: LaunchRequest) -> Result<Executable> {
     Ok(Executable {
         program: java,
         args,
-        cwd: None,
+        cwd: Some(request.get_game_dir_str()),
         kill_on_drop: false,
     })
 }
+
+async fn get_jvm_options(request: &LaunchRequest) -> Result<Vec<String>> {
+    let custom = &request.custom_info;
+    let java = &request.java_profile;
+    let allocation = custom.memory.allocate(
+        SystemMemory::read().await,
+        count_mods(&request.get_game_dir()).await,
+        java.is_64bit,
+    );
+    info!("Allocating {}m of heap to the game", allocation.max_mb);
+
+    let mut args = allocation.to_jvm_args();
+    if let Some(preset) = custom.jvm_preset {
+        args.extend(preset.jvm_args(java)?);
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_version_without_downloads_needs_its_local_jar() {
+        let root = TempDir::new().unwrap();
+        let jar = AbsPathBuf::new(&root.path().join("synthetic-legacy.jar")).unwrap();
+        let catalog = Arc::new(VersionCatalogClient::new(
+            Client::new(),
+            "http://127.0.0.1:9",
+        ));
+
+        let missing = download_client_jar(Client::new(), catalog.clone(), None, jar.clone()).await;
+        fs::write(&jar, b"local jar").await.unwrap();
+        let present = download_client_jar(Client::new(), catalog, None, jar).await;
+
+        assert!(missing.is_err());
+        assert!(present.is_ok());
+    }
+}
@@ -0,0 +1,397 @@
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeDelta};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::LazyLock;
+
+const XML_EVENT_START: &str = "<log4j:Event";
+const XML_EVENT_END: &str = "</log4j:Event>";
+// an event that never closes is given up on instead of buffering the whole output
+const MAX_PENDING_LINES: usize = 1000;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Type)]
+pub enum GameLogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+    Unknown,
+}
+
+impl GameLogLevel {
+    fn parse(level: &str) -> Self {
+        match level.trim().to_ascii_uppercase().as_str() {
+            "TRACE" | "FINEST" | "FINER" => GameLogLevel::Trace,
+            "DEBUG" | "FINE" | "CONFIG" => GameLogLevel::Debug,
+            "INFO" => GameLogLevel::Info,
+            "WARN" | "WARNING" => GameLogLevel::Warn,
+            "ERROR" | "SEVERE" => GameLogLevel::Error,
+            "FATAL" => GameLogLevel::Fatal,
+            _ => GameLogLevel::Unknown,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            GameLogLevel::Trace => "TRACE",
+            GameLogLevel::Debug => "DEBUG",
+            GameLogLevel::Info => "INFO",
+            GameLogLevel::Warn => "WARN",
+            GameLogLevel::Error => "ERROR",
+            GameLogLevel::Fatal => "FATAL",
+            GameLogLevel::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct GameLogRecord {
+    pub timestamp: Option<u64>, // unix millis
+    pub level: GameLogLevel,
+    pub thread: Option<String>,
+    pub logger: Option<String>,
+    pub message: String,
+    pub throwable: Option<String>,
+}
+
+impl GameLogRecord {
+    fn raw(message: impl Into<String>) -> Self {
+        GameLogRecord {
+            timestamp: None,
+            level: GameLogLevel::Unknown,
+            thread: None,
+            logger: None,
+            message: message.into(),
+            throwable: None,
+        }
+    }
+}
+
+impl fmt::Display for GameLogRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let time = self
+            .timestamp
+            .and_then(|ms| DateTime::from_timestamp_millis(ms as i64))
+            .map(|time| time.with_timezone(&Local).format("%H:%M:%S").to_string());
+        if let Some(time) = time {
+            write!(f, "[{time}] ")?;
+        }
+        match (&self.thread, self.level) {
+            (Some(thread), level) => write!(f, "[{thread}/{}]: ", level.as_str())?,
+            (None, GameLogLevel::Unknown) => {}
+            (None, level) => write!(f, "[{}]: ", level.as_str())?,
+        }
+        write!(f, "{}", self.message)?;
+        if let Some(throwable) = &self.throwable {
+            write!(f, "\n{throwable}")?;
+        }
+        Ok(())
+    }
+}
+
+// joins the multi-line log4j XML events, older versions print plain text instead
+#[derive(Debug, Default)]
+pub struct GameLogParser {
+    pending: Vec<String>,
+    last: Option<GameLogRecord>,
+    // the plain layout only prints the time of day, the date follows the previous record
+    last_timestamp: Option<u64>,
+}
+
+impl GameLogParser {
+    pub fn push_line(&mut self, line: &str) -> Option<GameLogRecord> {
+        if !self.pending.is_empty() {
+            self.pending.push(line.to_string());
+            if line.contains(XML_EVENT_END) {
+                return Some(self.flush_pending());
+            }
+            if self.pending.len() >= MAX_PENDING_LINES {
+                let raw = std::mem::take(&mut self.pending).join("\n");
+                return Some(GameLogRecord::raw(raw));
+            }
+            return None;
+        }
+        if line.trim_start().starts_with(XML_EVENT_START) {
+            self.pending.push(line.to_string());
+            return line.contains(XML_EVENT_END).then(|| self.flush_pending());
+        }
+        let record = self.parse_plain_line(line);
+        self.remember(&record);
+        Some(record)
+    }
+
+    // an unfinished XML event is emitted as raw text once the output has ended
+    pub fn finish(&mut self) -> Option<GameLogRecord> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        let raw = std::mem::take(&mut self.pending).join("\n");
+        Some(GameLogRecord::raw(raw))
+    }
+
+    fn flush_pending(&mut self) -> GameLogRecord {
+        let fragment = std::mem::take(&mut self.pending).join("\n");
+        let record = parse_xml_event(&fragment).unwrap_or_else(|| GameLogRecord::raw(fragment));
+        self.remember(&record);
+        record
+    }
+
+    fn remember(&mut self, record: &GameLogRecord) {
+        self.last_timestamp = record.timestamp.or(self.last_timestamp);
+        self.last = Some(record.clone());
+    }
+
+    fn parse_plain_line(&self, line: &str) -> GameLogRecord {
+        if let Some(record) = parse_plain_line(line, self.last_timestamp) {
+            return record;
+        }
+        match &self.last {
+            // stack traces belong to the previous record
+            Some(last) if is_continuation_line(line) => GameLogRecord {
+                message: line.to_string(),
+                throwable: None,
+                ..last.clone()
+            },
+            _ => GameLogRecord::raw(line),
+        }
+    }
+}
+
+fn is_continuation_line(line: &str) -> bool {
+    line.starts_with(char::is_whitespace) || line.starts_with("Caused by")
+}
+
+pub fn parse_xml_event(fragment: &str) -> Option<GameLogRecord> {
+    static EVENT_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?s)<log4j:Event\s([^>]*)>(.*?)</log4j:Event>")
+            .expect("Internal Error: Failed to compile log4j event regex")
+    });
+    static ATTRIBUTE_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r#"([\w:]+)="([^"]*)""#)
+            .expect("Internal Error: Failed to compile log4j attribute regex")
+    });
+
+    let captures = EVENT_RE.captures(fragment)?;
+    let attributes: HashMap<&str, String> = ATTRIBUTE_RE
+        .captures_iter(&captures[1])
+        .map(|c| (c.get(1).map_or("", |m| m.as_str()), unescape_xml(&c[2])))
+        .collect();
+    let body = &captures[2];
+    Some(GameLogRecord {
+        timestamp: attributes.get("timestamp").and_then(|t| t.parse().ok()),
+        level: attributes
+            .get("level")
+            .map_or(GameLogLevel::Unknown, |l| GameLogLevel::parse(l)),
+        thread: attributes.get("thread").cloned(),
+        logger: attributes.get("logger").cloned().filter(|l| !l.is_empty()),
+        message: xml_element_text(body, "Message").unwrap_or_default(),
+        throwable: xml_element_text(body, "Throwable"),
+    })
+}
+
+fn xml_element_text(body: &str, element: &str) -> Option<String> {
+    let start_tag = format!("<log4j:{element}>");
+    let end_tag = format!("</log4j:{element}>");
+    let start = body.find(&start_tag)? + start_tag.len();
+    let end = start + body[start..].find(&end_tag)?;
+    let text = body[start..end].trim();
+    let text = match text
+        .strip_prefix("<![CDATA[")
+        .and_then(|t| t.strip_suffix("]]>"))
+    {
+        Some(cdata) => cdata.to_string(),
+        None => unescape_xml(text),
+    };
+    Some(text.trim_end().to_string())
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// [12:34:56] [Render thread/INFO] [logger]: message, or before 1.7
+// 2013-09-18 14:11:23 [INFO] [logger] message
+pub fn parse_plain_line(line: &str, previous_timestamp: Option<u64>) -> Option<GameLogRecord> {
+    static MODERN_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r"^\[(\d{2}:\d{2}:\d{2})(?:\.\d+)?\] \[(.+?)/([A-Z]+)\](?: \[([^\]]+)\])?: (.*)$",
+        )
+        .expect("Internal Error: Failed to compile plain log regex")
+    });
+    static LEGACY_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^(\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}) \[([A-Z]+)\] (?:\[([^\]]+)\] )?(.*)$")
+            .expect("Internal Error: Failed to compile legacy log regex")
+    });
+
+    if let Some(c) = MODERN_RE.captures(line) {
+        let timestamp = NaiveTime::parse_from_str(&c[1], "%H:%M:%S")
+            .ok()
+            .and_then(|time| local_timestamp(anchor_time(time, previous_timestamp)));
+        return Some(GameLogRecord {
+            timestamp,
+            level: GameLogLevel::parse(&c[3]),
+            thread: Some(c[2].to_string()),
+            logger: c.get(4).map(|m| m.as_str().to_string()),
+            message: c[5].to_string(),
+            throwable: None,
+        });
+    }
+    let c = LEGACY_RE.captures(line)?;
+    Some(GameLogRecord {
+        timestamp: NaiveDateTime::parse_from_str(&c[1], "%Y-%m-%d %H:%M:%S")
+            .ok()
+            .and_then(local_timestamp),
+        level: GameLogLevel::parse(&c[2]),
+        thread: None,
+        logger: c.get(3).map(|m| m.as_str().to_string()),
+        message: c[4].to_string(),
+        throwable: None,
+    })
+}
+
+fn anchor_time(time: NaiveTime, previous_timestamp: Option<u64>) -> NaiveDateTime {
+    let previous = previous_timestamp
+        .and_then(|millis| DateTime::from_timestamp_millis(i64::try_from(millis).ok()?))
+        .map(|previous| previous.with_timezone(&Local).naive_local());
+    let Some(previous) = previous else {
+        return Local::now().date_naive().and_time(time);
+    };
+    let anchored = previous.date().and_time(time);
+    // a time far behind the previous record means the game ran past midnight
+    if previous - anchored > TimeDelta::hours(12) {
+        anchored + TimeDelta::days(1)
+    } else {
+        anchored
+    }
+}
+
+fn local_timestamp(time: NaiveDateTime) -> Option<u64> {
+    let millis = time
+        .and_local_timezone(Local)
+        .earliest()?
+        .timestamp_millis();
+    u64::try_from(millis).ok()
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    fn parse_all(lines: &[&str]) -> Vec<GameLogRecord> {
+        let mut parser = GameLogParser::default();
+        let mut records: Vec<_> = lines.iter().filter_map(|l| parser.push_line(l)).collect();
+        records.extend(parser.finish());
+        records
+    }
+
+    #[test]
+    fn test_parse_multiline_xml_event() {
+        let records = parse_all(&[
+            r#"<log4j:Event logger="net.minecraft.client.Minecraft" timestamp="1700000000000" level="ERROR" thread="Render thread">"#,
+            "  <log4j:Message><![CDATA[Unreported exception thrown!]]></log4j:Message>",
+            "  <log4j:Throwable><![CDATA[java.lang.IllegalStateException: boom",
+            "\tat net.minecraft.client.Minecraft.run(Minecraft.java:1)",
+            "]]></log4j:Throwable>",
+            "</log4j:Event>",
+        ]);
+
+        assert_eq!(
+            records,
+            [GameLogRecord {
+                timestamp: Some(1_700_000_000_000),
+                level: GameLogLevel::Error,
+                thread: Some("Render thread".to_string()),
+                logger: Some("net.minecraft.client.Minecraft".to_string()),
+                message: "Unreported exception thrown!".to_string(),
+                throwable: Some(
+                    "java.lang.IllegalStateException: boom\n\tat net.minecraft.client.Minecraft.run(Minecraft.java:1)"
+                        .to_string()
+                ),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_escaped_xml_attributes() {
+        let record = parse_xml_event(
+            r#"<log4j:Event logger="a&amp;b" timestamp="1" level="WARN" thread="&quot;main&quot;"><log4j:Message>1 &lt; 2</log4j:Message></log4j:Event>"#,
+        )
+        .unwrap();
+
+        assert_eq!(record.logger.as_deref(), Some("a&b"));
+        assert_eq!(record.thread.as_deref(), Some("\"main\""));
+        assert_eq!(record.message, "1 < 2");
+    }
+
+    #[test]
+    fn test_parse_plain_layouts() {
+        let records = parse_all(&[
+            "[12:34:56] [main/INFO] [FML]: Forge Mod Loader version 14.23.5",
+            "[12:34:57] [Client thread/WARN]: Skipping bad option",
+            "2013-09-18 14:11:23 [SEVERE] [Minecraft-Client] Unable to launch",
+        ]);
+
+        assert_eq!(records[0].level, GameLogLevel::Info);
+        assert_eq!(records[0].thread.as_deref(), Some("main"));
+        assert_eq!(records[0].logger.as_deref(), Some("FML"));
+        assert_eq!(records[0].message, "Forge Mod Loader version 14.23.5");
+        assert_eq!(records[1].thread.as_deref(), Some("Client thread"));
+        assert_eq!(records[1].logger, None);
+        assert_eq!(records[2].level, GameLogLevel::Error);
+        assert_eq!(records[2].logger.as_deref(), Some("Minecraft-Client"));
+        assert!(records.iter().all(|r| r.timestamp.is_some()));
+    }
+
+    #[test]
+    fn test_continuation_lines_inherit_previous_record() {
+        let records = parse_all(&[
+            "[12:34:56] [Render thread/ERROR]: Crashed",
+            "\tat foo.Bar.baz(Bar.java:1)",
+            "Caused by: java.lang.NullPointerException",
+            "Unrelated output of a mod",
+        ]);
+
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[1].level, GameLogLevel::Error);
+        assert_eq!(records[1].thread.as_deref(), Some("Render thread"));
+        assert_eq!(records[1].message, "\tat foo.Bar.baz(Bar.java:1)");
+        assert_eq!(records[2].level, GameLogLevel::Error);
+        assert_eq!(records[3].level, GameLogLevel::Unknown);
+        assert_eq!(records[3].thread, None);
+    }
+
+    #[test]
+    fn test_plain_time_follows_the_previous_record_date() {
+        let at = |date: &str| {
+            NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .and_then(local_timestamp)
+        };
+        let previous = at("2024-03-01 23:59:58");
+
+        let same_day = parse_plain_line("[23:59:59] [main/INFO]: Saving", previous).unwrap();
+        let next_day = parse_plain_line("[00:00:01] [main/INFO]: Saved", previous).unwrap();
+
+        assert_eq!(same_day.timestamp, at("2024-03-01 23:59:59"));
+        assert_eq!(next_day.timestamp, at("2024-03-02 00:00:01"));
+    }
+
+    #[test]
+    fn test_unfinished_xml_event_is_flushed_raw() {
+        let records = parse_all(&["Loading", r#"<log4j:Event logger="x" level="INFO">"#]);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].level, GameLogLevel::Unknown);
+        assert!(records[1].message.starts_with(XML_EVENT_START));
+    }
+}
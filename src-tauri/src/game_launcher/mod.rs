@@ -1,5 +1,13 @@
 mod arguments;
 mod classpath;
+pub mod commands;
+pub mod crash;
+mod events;
+pub mod jvm_options;
 pub mod launch;
+pub mod log_parser;
 pub mod models;
-mod rule;
+pub mod natives;
+pub mod process;
+pub mod rule;
+mod tests;
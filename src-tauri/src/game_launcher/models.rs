@@ -2,48 +2,79 @@ use crate::auth::microsoft::model::CLIENT_ID;
 use crate::auth::models::PlayerProfile;
 use crate::auth::models::UserType::Demo;
 use crate::constants::launcher::{LAUNCHER_NAME, LAUNCHER_VERSION, SHORT_LAUNCHER_NAME};
-use crate::constants::minecraft_dir::{ASSETS_DIR_NAME, LIBRARIES_DIR_NAME, VERSIONS_DIR_NAME};
+use crate::constants::minecraft_dir::{
+    ASSETS_DIR_NAME, LIBRARIES_DIR_NAME, NATIVES_DIR_NAME, VERSIONS_DIR_NAME,
+};
+use crate::game_assets::layout::get_game_assets_dir;
+use crate::game_assets::log_config::{get_log_config_path, get_logging_argument};
+use crate::game_assets::models::AssetLayout;
+use crate::game_launcher::jvm_options::{JvmFlagPreset, MemoryPolicy};
+use crate::game_launcher::natives::{NativeJar, collect_native_jars};
 use crate::game_resolver::VersionManifest;
 use crate::game_resolver::models::{
     Arguments, AssetIndex, Downloads, JavaVersion, Library, Logging, MinecraftFolderInfo,
 };
-use crate::java_runtime::models::JavaInstance;
+use crate::java_runtime::models::{JavaArch, JavaInstance};
 use crate::utils::abs_path_buf::AbsPathBuf;
 use LaunchError::IncompleteVersionManifest;
 use anyhow::Result;
+use chrono::{DateTime, NaiveDate};
 use os_info::Info;
 use regex::{Captures, Regex};
 use serde::{Deserialize, Serialize};
+use specta::Type;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::LazyLock;
 use tap::Pipe;
 use thiserror::Error;
+use uuid::Uuid;
 
 const DEFAULT_VERSION_INDEPENDENT: bool = true;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct LaunchRequest {
+    pub instance_id: Uuid,
     pub minecraft_folder_info: MinecraftFolderInfo,
     pub manifest: LaunchVersionManifest,
     pub player_profile: PlayerProfile,
     pub java_profile: JavaInstance,
     pub custom_info: CustomInfo,
+    pub asset_layout: AssetLayout,
+}
+
+impl MinecraftFolderInfo {
+    pub fn game_dir(&self, version_id: &str) -> AbsPathBuf {
+        if self
+            .settings
+            .is_version_independent
+            .unwrap_or(DEFAULT_VERSION_INDEPENDENT)
+        {
+            self.path.join(VERSIONS_DIR_NAME).join(version_id)
+        } else {
+            self.path.clone()
+        }
+    }
 }
 
 impl LaunchRequest {
     pub fn new(
+        instance_id: Uuid,
         minecraft_folder_info: MinecraftFolderInfo,
         manifest: LaunchVersionManifest,
         java_profile: JavaInstance,
         custom_info: CustomInfo,
         player_profile: PlayerProfile,
+        asset_layout: AssetLayout,
     ) -> Result<Self> {
         Ok(LaunchRequest {
+            instance_id,
             minecraft_folder_info,
             manifest,
             java_profile,
             custom_info,
             player_profile,
+            asset_layout,
         })
     }
 
@@ -54,10 +85,7 @@ impl LaunchRequest {
             .iter()
             .filter_map(|l| l.to_classpath_entry(self.get_libraries_dir(), self.get_rule_context()))
             .collect::<Vec<AbsPathBuf>>();
-        let game_jar = self
-            .get_game_dir()
-            .join(format!("{}.jar", self.manifest.id));
-        let res = [libraries, vec![game_jar]]
+        let res = [libraries, vec![self.get_game_jar_path()]]
             .concat()
             .pipe(std::env::join_paths)?
             .to_string_lossy()
@@ -65,12 +93,38 @@ impl LaunchRequest {
         Ok(res)
     }
 
+    // versions without a jar of their own run the jar of the version they inherit from
+    pub fn get_game_jar_path(&self) -> AbsPathBuf {
+        let jar_of = |id: &str| {
+            self.minecraft_folder_info
+                .path
+                .join(VERSIONS_DIR_NAME)
+                .join(id)
+                .join(format!("{id}.jar"))
+        };
+        let own_jar = jar_of(&self.manifest.id);
+        match &self.manifest.inherits_from {
+            Some(parent) if !own_jar.is_file() => jar_of(parent),
+            _ => own_jar,
+        }
+    }
+
+    // each launch extracts its own natives, another instance of the version may still use theirs
     pub fn get_natives_dir(&self) -> AbsPathBuf {
         self.minecraft_folder_info
             .path
             .join(VERSIONS_DIR_NAME)
             .join(self.manifest.id.clone())
-            .join(VERSIONS_DIR_NAME)
+            .join(NATIVES_DIR_NAME)
+            .join(self.instance_id.to_string())
+    }
+
+    pub fn get_native_jars(&self) -> Vec<NativeJar> {
+        collect_native_jars(
+            &self.manifest.libraries,
+            &self.get_libraries_dir(),
+            &self.get_rule_context(),
+        )
     }
 
     pub fn get_libraries_dir(&self) -> AbsPathBuf {
@@ -82,19 +136,7 @@ impl LaunchRequest {
     }
 
     pub fn get_game_dir(&self) -> AbsPathBuf {
-        if self
-            .minecraft_folder_info
-            .settings
-            .is_version_independent
-            .unwrap_or(DEFAULT_VERSION_INDEPENDENT)
-        {
-            self.minecraft_folder_info
-                .path
-                .join(VERSIONS_DIR_NAME)
-                .join(self.manifest.id.clone())
-        } else {
-            self.minecraft_folder_info.path.clone()
-        }
+        self.minecraft_folder_info.game_dir(&self.manifest.id)
     }
 
     pub fn get_game_dir_str(&self) -> String {
@@ -109,6 +151,34 @@ impl LaunchRequest {
         self.get_assets_dir().to_string_lossy().to_string()
     }
 
+    pub fn get_game_assets_dir(&self) -> AbsPathBuf {
+        get_game_assets_dir(
+            self.asset_layout,
+            &self.get_assets_dir(),
+            &self.manifest.asset_index.id,
+            &self.get_game_dir(),
+        )
+    }
+
+    pub fn get_game_assets_dir_str(&self) -> String {
+        self.get_game_assets_dir().to_string_lossy().to_string()
+    }
+
+    pub fn get_log_config_path(&self) -> Option<AbsPathBuf> {
+        self.custom_info.custom_log_config.clone().or_else(|| {
+            let config = self.manifest.logging.client.as_ref()?;
+            Some(get_log_config_path(&self.get_assets_dir(), config))
+        })
+    }
+
+    pub fn get_logging_argument(&self) -> Option<String> {
+        let path = self.get_log_config_path()?;
+        Some(get_logging_argument(
+            self.manifest.logging.client.as_ref(),
+            &path,
+        ))
+    }
+
     pub fn get_rule_context(&self) -> RuleContext {
         RuleContext {
             os_info: os_info::get(),
@@ -155,7 +225,7 @@ impl LaunchRequest {
             natives_directory: self.get_natives_dir_str()?,
             assets_index_name: self.manifest.asset_index.id.clone(),
             assets_root: self.get_assets_dir_str(),
-            game_assets: self.get_assets_dir_str(),
+            game_assets: self.get_game_assets_dir_str(),
             resolution_width: custom.custom_resolution.clone().unwrap_or_default().width,
             resolution_height: custom.custom_resolution.clone().unwrap_or_default().height,
             quick_play_single_player: custom.quick_play.get_single_player().unwrap_or_default(),
@@ -171,23 +241,67 @@ impl LaunchRequest {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchHandle {
+    pub task_id: Uuid,
+    pub instance_id: Uuid,
+    pub version_id: String,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct CustomInfo {
     pub quick_play: QuickPlayInfo,
+    pub memory: MemoryPolicy,
+    pub jvm_preset: Option<JvmFlagPreset>,
     pub custom_jvm_args: Vec<String>,
     pub custom_game_args: Vec<String>,
     pub custom_resolution: Option<GameResolution>,
+    // replaces the log4j config shipped with the version
+    pub custom_log_config: Option<AbsPathBuf>,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq)]
+// what the frontend picks for a launch, turned into a CustomInfo
+#[derive(Debug, Clone, Default, Deserialize, Type)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LaunchSettings {
+    pub quick_play: QuickPlayInfo,
+    pub memory: MemoryPolicy,
+    pub jvm_preset: Option<JvmFlagPreset>,
+    pub custom_jvm_args: Vec<String>,
+    pub custom_game_args: Vec<String>,
+    pub custom_resolution: Option<GameResolution>,
+    pub custom_log_config: Option<PathBuf>,
+}
+
+impl TryFrom<LaunchSettings> for CustomInfo {
+    type Error = anyhow::Error;
+
+    fn try_from(settings: LaunchSettings) -> Result<Self> {
+        Ok(Self {
+            quick_play: settings.quick_play,
+            memory: settings.memory,
+            jvm_preset: settings.jvm_preset,
+            custom_jvm_args: settings.custom_jvm_args,
+            custom_game_args: settings.custom_game_args,
+            custom_resolution: settings.custom_resolution,
+            custom_log_config: settings
+                .custom_log_config
+                .map(AbsPathBuf::try_from)
+                .transpose()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct GameResolution {
     pub width: u64,
     pub height: u64,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq, Type)]
 #[serde(rename_all = "camelCase")]
 pub enum QuickPlayInfo {
     #[default]
@@ -300,7 +414,7 @@ pub struct LaunchVersionManifest {
     pub asset_index: AssetIndex,
     pub assets: String,
     pub compliance_level: u32,
-    pub downloads: Downloads,
+    pub downloads: Option<Downloads>, // None means only the local jar can be used
     pub id: String,
     pub java_version: JavaVersion,
     pub libraries: Vec<Library>,
@@ -330,8 +444,20 @@ pub enum LaunchError {
     IncompleteVersionManifest,
     #[error("Invalid java runtime")]
     InvalidJavaRuntime,
+    #[error("Java {actual} ({arch:?}) can't run this version, it needs java {required}")]
+    IncompatibleJava {
+        required: u32,
+        actual: u32,
+        arch: JavaArch,
+    },
     #[error("Invalid version '{0}'")]
     InvalidVersionName(String),
+    #[error("JVM preset {preset:?} needs java {required} or newer, but java {actual} is selected")]
+    UnsupportedJvmPreset {
+        preset: JvmFlagPreset,
+        required: u32,
+        actual: u32,
+    },
 }
 
 impl TryFrom<VersionManifest> for LaunchVersionManifest {
@@ -346,18 +472,19 @@ impl TryFrom<VersionManifest> for LaunchVersionManifest {
             return Err(IncompleteVersionManifest);
         };
         let asset_index = manifest.asset_index.ok_or(IncompleteVersionManifest)?;
-        let assets = manifest.assets.ok_or(IncompleteVersionManifest)?;
-        let compliance_level = manifest.compliance_level.ok_or(IncompleteVersionManifest)?;
-        let downloads = manifest.downloads.ok_or(IncompleteVersionManifest)?;
-        let logging = manifest.logging.ok_or(IncompleteVersionManifest)?;
-        let java_version = manifest.java_version.ok_or(IncompleteVersionManifest)?;
+        let assets = manifest.assets.unwrap_or_else(|| asset_index.id.clone());
+        let compliance_level = manifest.compliance_level.unwrap_or_default();
+        let logging = manifest.logging.unwrap_or(Logging { client: None });
+        let java_version = manifest
+            .java_version
+            .unwrap_or_else(|| infer_java_version(&manifest.release_time));
 
         Ok(LaunchVersionManifest {
             arguments,
             asset_index,
             assets,
             compliance_level,
-            downloads,
+            downloads: manifest.downloads,
             id: manifest.id,
             java_version,
             libraries: manifest.libraries,
@@ -371,10 +498,42 @@ impl TryFrom<VersionManifest> for LaunchVersionManifest {
     }
 }
 
+// first snapshots requiring each java major, see https://minecraft.wiki/w/Tutorials/Update_Java
+const JAVA_REQUIREMENTS: &[((i32, u32, u32), u32, &str)] = &[
+    ((2024, 4, 3), 21, "java-runtime-delta"),  // 24w14a
+    ((2021, 11, 3), 17, "java-runtime-gamma"), // 21w44a
+    ((2021, 5, 12), 16, "java-runtime-alpha"), // 21w19a
+];
+const LEGACY_JAVA_MAJOR_VERSION: u32 = 8;
+const LEGACY_JAVA_COMPONENT: &str = "jre-legacy";
+
+pub fn infer_java_version(release_time: &str) -> JavaVersion {
+    // loader profiles often write the offset without a colon, e.g. +0000
+    let release_date = DateTime::parse_from_rfc3339(release_time)
+        .or_else(|_| DateTime::parse_from_str(release_time, "%Y-%m-%dT%H:%M:%S%z"))
+        .map(|time| time.date_naive())
+        .ok();
+
+    JAVA_REQUIREMENTS
+        .iter()
+        .find(|((year, month, day), _, _)| {
+            let since = NaiveDate::from_ymd_opt(*year, *month, *day);
+            release_date.is_some_and(|date| since.is_some_and(|since| date >= since))
+        })
+        .map(|(_, major_version, component)| JavaVersion {
+            component: component.to_string(),
+            major_version: *major_version,
+        })
+        .unwrap_or_else(|| JavaVersion {
+            component: LEGACY_JAVA_COMPONENT.into(),
+            major_version: LEGACY_JAVA_MAJOR_VERSION,
+        })
+}
+
 #[cfg(test)]
 #[cfg_attr(coverage_nightly, coverage(off))]
 mod tests {
-    use crate::game_launcher::models::ArgumentsContext;
+    use crate::game_launcher::models::{ArgumentsContext, infer_java_version};
     use std::collections::HashMap;
 
     #[test]
@@ -407,4 +566,27 @@ mod tests {
             "Placeholder should be replaced with \"\" when key is unknown"
         );
     }
+
+    #[test]
+    fn test_infer_java_version_from_release_time() {
+        let cases = [
+            ("2010-06-30T22:00:00+00:00", 8),
+            ("2019-12-10T10:25:31+00:00", 8),
+            ("2021-06-08T11:00:40+00:00", 16),
+            ("2023-06-12T13:25:51+00:00", 17),
+            ("2024-04-23T12:49:42+00:00", 21),
+            ("2024-11-29T10:36:44+0000", 21),
+            ("2021-06-08T11:00:40+0000", 16),
+            ("", 8),
+            ("not a date", 8),
+        ];
+
+        for (release_time, expected) in cases {
+            assert_eq!(
+                infer_java_version(release_time).major_version,
+                expected,
+                "Unexpected java version for release time {release_time:?}"
+            );
+        }
+    }
 }
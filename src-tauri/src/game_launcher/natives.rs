@@ -0,0 +1,178 @@
+use crate::game_launcher::models::RuleContext;
+use crate::game_launcher::rule::{platform_name, should_apply_rules};
+use crate::game_resolver::models::Library;
+use crate::scheduler::{Task, task};
+use crate::utils::abs_path_buf::AbsPathBuf;
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NativeJar {
+    pub name: String,
+    pub path: AbsPathBuf,
+    pub exclude: Vec<String>,
+}
+
+// natives-windows-${arch} becomes natives-windows-64
+pub fn native_classifier(library: &Library, rule_context: &RuleContext) -> Option<String> {
+    let natives = library.natives.as_ref()?;
+    let platform = platform_name(rule_context.os_info.os_type());
+    let classifier = native_arch_suffix(rule_context)
+        .and_then(|suffix| natives.get(&format!("{platform}-{suffix}")))
+        .or_else(|| natives.get(platform))?;
+    Some(classifier.replace("${arch}", native_bitness(rule_context)))
+}
+
+fn native_arch_suffix(rule_context: &RuleContext) -> Option<&'static str> {
+    let arch = rule_context.os_info.architecture()?.to_lowercase();
+    (arch.contains("aarch64") || arch.contains("arm64")).then_some("arm64")
+}
+
+fn native_bitness(rule_context: &RuleContext) -> &'static str {
+    match rule_context.os_info.bitness() {
+        os_info::Bitness::X32 => "32",
+        _ => "64",
+    }
+}
+
+// the classifier download path wins over the maven coordinate
+pub fn native_jar_path(
+    library: &Library,
+    classifier: &str,
+    libraries_dir: &AbsPathBuf,
+) -> Option<AbsPathBuf> {
+    let download_path = library
+        .downloads
+        .as_ref()
+        .and_then(|d| d.classifiers.as_ref())
+        .and_then(|c| c.get(classifier))
+        .and_then(|file| file.path.as_ref());
+    match download_path {
+        Some(path) => Some(libraries_dir.join(path)),
+        None => Library {
+            name: format!("{}:{classifier}", library.name),
+            ..library.clone()
+        }
+        .get_jar_path(libraries_dir.clone()),
+    }
+}
+
+pub fn collect_native_jars(
+    libraries: &[Library],
+    libraries_dir: &AbsPathBuf,
+    rule_context: &RuleContext,
+) -> Vec<NativeJar> {
+    let mut seen_paths = HashSet::new();
+    libraries
+        .iter()
+        .filter(|library| {
+            library
+                .rules
+                .clone()
+                .is_none_or(|rules| should_apply_rules(rules, rule_context.clone()))
+        })
+        .filter_map(|library| {
+            let classifier = native_classifier(library, rule_context)?;
+            Some(NativeJar {
+                name: format!("{}:{classifier}", library.name),
+                path: native_jar_path(library, &classifier, libraries_dir)?,
+                exclude: library
+                    .extract
+                    .as_ref()
+                    .map(|e| e.exclude.clone())
+                    .unwrap_or_default(),
+            })
+        })
+        .filter(|jar| seen_paths.insert(jar.path.clone()))
+        .collect()
+}
+
+pub fn extract_natives(jars: &[NativeJar], natives_dir: &Path) -> Result<()> {
+    fs::create_dir_all(natives_dir)
+        .with_context(|| format!("Failed to create directory {}", natives_dir.display()))?;
+    let mut extracted = HashSet::new();
+    for jar in jars {
+        extract_jar(jar, natives_dir, &mut extracted)
+            .with_context(|| format!("Failed to extract natives from {}", jar.name))?;
+    }
+    Ok(())
+}
+
+fn extract_jar(
+    jar: &NativeJar,
+    natives_dir: &Path,
+    extracted: &mut HashSet<PathBuf>,
+) -> Result<()> {
+    let file =
+        File::open(&jar.path).with_context(|| format!("Failed to open {}", jar.path.display()))?;
+    let mut archive = ZipArchive::new(file)?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir() || jar.exclude.iter().any(|p| entry.name().starts_with(p)) {
+            continue;
+        }
+        // entries escaping the natives dir are ignored
+        let Some(relative_path) = entry.enclosed_name() else {
+            continue;
+        };
+        if !extracted.insert(relative_path.clone()) {
+            continue;
+        }
+        let dest = natives_dir.join(&relative_path);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut output = File::create(&dest)
+            .with_context(|| format!("Failed to create file {}", dest.display()))?;
+        io::copy(&mut entry, &mut output)?;
+    }
+    Ok(())
+}
+
+// launches whose game is gone, e.g. the launcher was closed while it ran, leave their natives behind
+pub fn remove_unused_natives(natives_root: &Path, in_use: &HashSet<String>) -> Result<()> {
+    let entries = match fs::read_dir(natives_root) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    for entry in entries {
+        let entry = entry?;
+        if in_use.contains(entry.file_name().to_string_lossy().as_ref()) {
+            continue;
+        }
+        let path = entry.path();
+        let removed = if entry.file_type()?.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        removed.with_context(|| format!("Failed to remove unused natives {}", path.display()))?;
+    }
+    Ok(())
+}
+
+// in_use names the natives of games still running, they sit next to the natives of this launch
+pub fn extract_natives_task(
+    jars: Vec<NativeJar>,
+    natives_dir: AbsPathBuf,
+    in_use: HashSet<String>,
+) -> impl Task<Input = (), Output = ()> {
+    task("Extract natives", move |_: ()| {
+        let (jars, natives_dir, in_use) = (jars.clone(), natives_dir.clone(), in_use.clone());
+        async move {
+            tokio::task::spawn_blocking(move || {
+                if let Some(natives_root) = natives_dir.parent() {
+                    remove_unused_natives(natives_root, &in_use)?;
+                }
+                extract_natives(&jars, &natives_dir)
+            })
+            .await?
+        }
+    })
+}
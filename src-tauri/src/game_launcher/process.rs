@@ -0,0 +1,433 @@
+use crate::game_launcher::crash::{
+    CrashContext, CrashDiagnosis, analyze_crash, collect_crash_evidence,
+};
+use crate::game_launcher::log_parser::{GameLogParser, GameLogRecord};
+use crate::utils::executor::RunningProcess;
+use anyhow::{Result, bail};
+use dashmap::DashMap;
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::VecDeque;
+use std::process::ExitStatus;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+const MAX_KEPT_LOG_RECORDS: usize = 2000;
+// exited games stay around for their logs and diagnosis, the oldest are dropped past this
+const MAX_KEPT_EXITED_PROCESSES: usize = 8;
+const LOG_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Type)]
+pub enum GameProcessState {
+    Running,
+    Exited,
+    Killed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct GameProcessInfo {
+    pub instance_id: Uuid,
+    pub version_id: String,
+    pub pid: Option<u32>,
+    pub started_at: u64, // unix millis
+    pub state: GameProcessState,
+    pub exit_code: Option<i32>,
+    pub running_time_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum GameProcessEvent {
+    Log {
+        instance_id: Uuid,
+        record: GameLogRecord,
+    },
+    Exited {
+        info: GameProcessInfo,
+        diagnosis: Option<CrashDiagnosis>,
+    },
+}
+
+pub type GameProcessEventSink = Arc<dyn Fn(GameProcessEvent) + Send + Sync>;
+
+struct TrackedProcess {
+    info: GameProcessInfo,
+    logs: VecDeque<GameLogRecord>,
+    kill_token: CancellationToken,
+    crash_context: CrashContext,
+    diagnosis: Option<CrashDiagnosis>,
+}
+
+pub struct GameProcessRegistry {
+    processes: DashMap<Uuid, TrackedProcess>,
+    // oldest first
+    exited: Mutex<VecDeque<Uuid>>,
+    sink: GameProcessEventSink,
+}
+
+enum WatchResult {
+    Exited(std::io::Result<ExitStatus>),
+    KillRequested,
+}
+
+impl GameProcessRegistry {
+    pub fn new(sink: GameProcessEventSink) -> Self {
+        Self {
+            processes: DashMap::new(),
+            exited: Mutex::new(VecDeque::new()),
+            sink,
+        }
+    }
+
+    pub fn track(
+        self: &Arc<Self>,
+        instance_id: Uuid,
+        version_id: impl Into<String>,
+        process: RunningProcess,
+        crash_context: CrashContext,
+    ) -> JoinHandle<GameProcessInfo> {
+        let kill_token = CancellationToken::new();
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+        let info = GameProcessInfo {
+            instance_id,
+            version_id: version_id.into(),
+            pid: process.child.id(),
+            started_at,
+            state: GameProcessState::Running,
+            exit_code: None,
+            running_time_ms: None,
+        };
+        self.processes.insert(
+            instance_id,
+            TrackedProcess {
+                info,
+                logs: VecDeque::new(),
+                kill_token: kill_token.clone(),
+                crash_context,
+                diagnosis: None,
+            },
+        );
+
+        let registry = self.clone();
+        tokio::spawn(async move { registry.watch(instance_id, process, kill_token).await })
+    }
+
+    pub fn get(&self, instance_id: Uuid) -> Option<GameProcessInfo> {
+        self.processes.get(&instance_id).map(|p| p.info.clone())
+    }
+
+    pub fn list_running(&self) -> Vec<GameProcessInfo> {
+        self.processes
+            .iter()
+            .filter(|p| p.info.state == GameProcessState::Running)
+            .map(|p| p.info.clone())
+            .collect()
+    }
+
+    pub fn logs(&self, instance_id: Uuid) -> Vec<GameLogRecord> {
+        self.processes
+            .get(&instance_id)
+            .map(|p| p.logs.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// The crash analysis of a game that exited with a non-zero code.
+    pub fn diagnosis(&self, instance_id: Uuid) -> Option<CrashDiagnosis> {
+        self.processes
+            .get(&instance_id)
+            .and_then(|p| p.diagnosis.clone())
+    }
+
+    pub fn kill(&self, instance_id: Uuid) -> Result<()> {
+        let Some(process) = self.processes.get(&instance_id) else {
+            bail!("No game process with id {instance_id}");
+        };
+        if process.info.state != GameProcessState::Running {
+            bail!("Game process {instance_id} is not running");
+        }
+        process.kill_token.cancel();
+        Ok(())
+    }
+
+    async fn watch(
+        &self,
+        instance_id: Uuid,
+        mut process: RunningProcess,
+        kill_token: CancellationToken,
+    ) -> GameProcessInfo {
+        let started = Instant::now();
+        let mut parser = GameLogParser::default();
+        let mut logs_open = true;
+        let result = loop {
+            // the child is only touched again after select! has dropped its futures
+            let result = tokio::select! {
+                line = process.log_receiver.recv(), if logs_open => {
+                    match line {
+                        Some(line) => self.push_log(instance_id, &mut parser, &line),
+                        None => logs_open = false,
+                    }
+                    continue;
+                }
+                status = process.child.wait() => WatchResult::Exited(status),
+                _ = kill_token.cancelled() => WatchResult::KillRequested,
+            };
+            break result;
+        };
+
+        let (state, status) = match result {
+            WatchResult::Exited(status) => (GameProcessState::Exited, status),
+            WatchResult::KillRequested => {
+                if let Err(e) = process.child.kill().await {
+                    warn!("Failed to kill game process {instance_id}: {e}");
+                }
+                (GameProcessState::Killed, process.child.wait().await)
+            }
+        };
+        let running_time = started.elapsed();
+
+        // lines still buffered in the pipe when the game exited
+        let _ = timeout(LOG_DRAIN_TIMEOUT, async {
+            while let Some(line) = process.log_receiver.recv().await {
+                self.push_log(instance_id, &mut parser, &line);
+            }
+        })
+        .await;
+        if let Some(record) = parser.finish() {
+            self.push_record(instance_id, record);
+        }
+
+        let exit_code = status.ok().and_then(|s| s.code());
+        let info = self.finish(instance_id, state, exit_code, running_time);
+        info!(
+            "Game {} ({instance_id}) {state:?} with code {exit_code:?} after {running_time:?}",
+            info.version_id
+        );
+        let diagnosis = match state {
+            GameProcessState::Exited if exit_code != Some(0) => {
+                Some(self.diagnose(instance_id, &info, exit_code).await)
+            }
+            _ => None,
+        };
+        (self.sink)(GameProcessEvent::Exited {
+            info: info.clone(),
+            diagnosis,
+        });
+        self.evict_exited(instance_id);
+        info
+    }
+
+    fn evict_exited(&self, instance_id: Uuid) {
+        let mut exited = self.exited.lock();
+        exited.push_back(instance_id);
+        while exited.len() > MAX_KEPT_EXITED_PROCESSES {
+            if let Some(oldest) = exited.pop_front() {
+                self.processes.remove(&oldest);
+            }
+        }
+    }
+
+    async fn diagnose(
+        &self,
+        instance_id: Uuid,
+        info: &GameProcessInfo,
+        exit_code: Option<i32>,
+    ) -> CrashDiagnosis {
+        let context = self
+            .processes
+            .get(&instance_id)
+            .map(|p| p.crash_context.clone())
+            .expect("Internal Error: Tracked process should never be removed while running");
+        let started_at = UNIX_EPOCH + Duration::from_millis(info.started_at);
+        let logs: Vec<String> = self
+            .logs(instance_id)
+            .iter()
+            .map(|record| record.to_string())
+            .collect();
+        let evidence = collect_crash_evidence(&context, &logs, started_at).await;
+        let diagnosis = analyze_crash(&evidence, &context, exit_code);
+        info!(
+            "Game {instance_id} crashed, possible causes: {:?}",
+            diagnosis.causes.iter().map(|c| c.cause).collect::<Vec<_>>()
+        );
+        if let Some(mut process) = self.processes.get_mut(&instance_id) {
+            process.diagnosis = Some(diagnosis.clone());
+        }
+        diagnosis
+    }
+
+    fn push_log(&self, instance_id: Uuid, parser: &mut GameLogParser, line: &str) {
+        if let Some(record) = parser.push_line(line) {
+            self.push_record(instance_id, record);
+        }
+    }
+
+    fn push_record(&self, instance_id: Uuid, record: GameLogRecord) {
+        if let Some(mut process) = self.processes.get_mut(&instance_id) {
+            if process.logs.len() >= MAX_KEPT_LOG_RECORDS {
+                process.logs.pop_front();
+            }
+            process.logs.push_back(record.clone());
+        }
+        (self.sink)(GameProcessEvent::Log {
+            instance_id,
+            record,
+        });
+    }
+
+    fn finish(
+        &self,
+        instance_id: Uuid,
+        state: GameProcessState,
+        exit_code: Option<i32>,
+        running_time: Duration,
+    ) -> GameProcessInfo {
+        let mut process = self
+            .processes
+            .get_mut(&instance_id)
+            .expect("Internal Error: Tracked process should never be removed while running");
+        process.info.state = state;
+        process.info.exit_code = exit_code;
+        process.info.running_time_ms = Some(running_time.as_millis() as u64);
+        process.info.clone()
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::game_launcher::crash::CrashCause;
+    use crate::utils::abs_path_buf::AbsPathBuf;
+    use crate::utils::executor::Executable;
+    use tempfile::TempDir;
+
+    fn shell(script: &str) -> Executable {
+        Executable {
+            program: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            cwd: None,
+            kill_on_drop: true,
+        }
+    }
+
+    fn crash_context(game_dir: &TempDir) -> CrashContext {
+        CrashContext {
+            game_dir: AbsPathBuf::new(game_dir.path()).unwrap(),
+            java_major_version: 21,
+            required_java_major_version: 21,
+            max_memory_mb: None,
+        }
+    }
+
+    fn registry() -> (Arc<GameProcessRegistry>, Arc<Mutex<Vec<GameProcessEvent>>>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink_events = events.clone();
+        let sink: GameProcessEventSink = Arc::new(move |event| sink_events.lock().push(event));
+        (Arc::new(GameProcessRegistry::new(sink)), events)
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_logs_and_exit_code_are_recorded() {
+        let (registry, events) = registry();
+        let game_dir = TempDir::new().unwrap();
+        let id = Uuid::new_v4();
+        let process = shell("echo loading; echo done >&2; exit 3")
+            .spawn()
+            .unwrap();
+
+        let info = registry
+            .track(id, "1.20.4", process, crash_context(&game_dir))
+            .await
+            .unwrap();
+
+        assert_eq!(info.state, GameProcessState::Exited);
+        assert_eq!(info.exit_code, Some(3));
+        assert!(info.running_time_ms.is_some());
+        let messages: Vec<_> = registry.logs(id).into_iter().map(|r| r.message).collect();
+        assert_eq!(messages, ["loading", "done"]);
+        assert!(registry.list_running().is_empty());
+        let diagnosis = registry.diagnosis(id).unwrap();
+        assert_eq!(diagnosis.exit_code, Some(3));
+        assert_eq!(diagnosis.causes[0].cause, CrashCause::Unknown);
+        assert_eq!(
+            events.lock().last(),
+            Some(&GameProcessEvent::Exited {
+                info: info.clone(),
+                diagnosis: Some(diagnosis),
+            })
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_crash_is_diagnosed_from_output() {
+        let (registry, _) = registry();
+        let game_dir = TempDir::new().unwrap();
+        let id = Uuid::new_v4();
+        let process = shell("echo 'java.lang.OutOfMemoryError: Java heap space' >&2; exit 1")
+            .spawn()
+            .unwrap();
+
+        registry
+            .track(id, "1.20.4", process, crash_context(&game_dir))
+            .await
+            .unwrap();
+
+        let diagnosis = registry.diagnosis(id).unwrap();
+        assert_eq!(diagnosis.causes[0].cause, CrashCause::OutOfMemory);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_kill_running_game() {
+        let (registry, _) = registry();
+        let game_dir = TempDir::new().unwrap();
+        let id = Uuid::new_v4();
+        let process = shell("exec sleep 30").spawn().unwrap();
+        let watcher = registry.track(id, "1.12.2", process, crash_context(&game_dir));
+
+        assert_eq!(registry.list_running()[0].instance_id, id);
+        registry.kill(id).unwrap();
+        let info = watcher.await.unwrap();
+
+        assert_eq!(info.state, GameProcessState::Killed);
+        assert!(
+            registry.diagnosis(id).is_none(),
+            "A killed game is not a crash"
+        );
+        assert!(
+            registry.kill(id).is_err(),
+            "A stopped game cannot be killed"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_only_recent_exited_games_are_kept() {
+        let (registry, _) = registry();
+        let game_dir = TempDir::new().unwrap();
+        let mut ids = vec![];
+        for _ in 0..=MAX_KEPT_EXITED_PROCESSES {
+            let id = Uuid::new_v4();
+            let process = shell("exit 0").spawn().unwrap();
+            registry
+                .track(id, "1.20.4", process, crash_context(&game_dir))
+                .await
+                .unwrap();
+            ids.push(id);
+        }
+
+        assert!(registry.get(ids[0]).is_none());
+        assert!(ids[1..].iter().all(|id| registry.get(*id).is_some()));
+        assert_eq!(registry.processes.len(), MAX_KEPT_EXITED_PROCESSES);
+    }
+}
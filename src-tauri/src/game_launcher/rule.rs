@@ -17,6 +17,15 @@ pub fn should_apply_rules(rules: Vec<Rule>, context: RuleContext) -> bool {
     should_apply
 }
 
+pub fn platform_name(os_type: Type) -> &'static str {
+    match os_type {
+        Type::Linux => "linux",
+        Type::Windows => "windows",
+        Type::Macos => "osx",
+        _ => "unknown",
+    }
+}
+
 impl Rule {
     pub fn should_apply(&self, context: RuleContext) -> bool {
         if self.is_match(context.clone()) {
@@ -39,7 +48,7 @@ impl Rule {
     }
 
     fn match_name(&self, os_name: Option<String>, info: &Info) -> bool {
-        os_name.is_none_or(|required| required == self.get_platform_name(info.os_type()))
+        os_name.is_none_or(|required| required == platform_name(info.os_type()))
     }
 
     fn match_arch(&self, os_arch: Option<String>, info: &Info) -> bool {
@@ -61,15 +70,6 @@ impl Rule {
         })
     }
 
-    fn get_platform_name(&self, os_type: Type) -> &'static str {
-        match os_type {
-            Type::Linux => "linux",
-            Type::Windows => "windows",
-            Type::Macos => "osx",
-            _ => "unknown",
-        }
-    }
-
     fn is_feature_supported(&self, user_features: &HashMap<String, bool>) -> bool {
         self.features.as_ref().is_none_or(|required| {
             required
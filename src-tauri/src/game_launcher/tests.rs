@@ -0,0 +1,307 @@
+#![cfg_attr(coverage_nightly, coverage(off))]
+#![cfg(test)]
+
+use crate::game_launcher::models::{ArgumentsInfo, LaunchVersionManifest, RuleContext};
+use crate::game_launcher::natives::{
+    NativeJar, collect_native_jars, extract_natives, remove_unused_natives,
+};
+use crate::game_resolver::VersionManifest;
+use crate::game_resolver::models::Library;
+use crate::utils::abs_path_buf::AbsPathBuf;
+use crate::utils::os_info::mock_info;
+use os_info::Type;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use tempfile::TempDir;
+use uuid::Uuid;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+fn parse_manifest(json: &str) -> VersionManifest {
+    serde_json::from_str(json).expect("Fixture should be a valid version manifest")
+}
+
+fn to_launch_manifest(manifest: VersionManifest) -> LaunchVersionManifest {
+    let id = manifest.id.clone();
+    LaunchVersionManifest::try_from(manifest)
+        .unwrap_or_else(|e| panic!("Version {id} should be launchable: {e}"))
+}
+
+#[test]
+fn test_manifests_from_every_era_are_launchable() {
+    let fixtures = [
+        (
+            include_str!("../../tests/fixtures/manifests/a1.0.4.json"),
+            8,
+        ),
+        (
+            include_str!("../../tests/fixtures/manifests/b1.7.3.json"),
+            8,
+        ),
+        (
+            include_str!("../../tests/fixtures/manifests/1.7.10.json"),
+            8,
+        ),
+        (
+            include_str!("../../tests/fixtures/manifests/1.12.2.json"),
+            8,
+        ),
+        (
+            include_str!("../../tests/fixtures/manifests/1.20.4.json"),
+            17,
+        ),
+    ];
+
+    for (json, expected_java) in fixtures {
+        let manifest = to_launch_manifest(parse_manifest(json));
+
+        assert_eq!(
+            manifest.java_version.major_version, expected_java,
+            "Unexpected java version for {}",
+            manifest.id
+        );
+        assert!(
+            manifest.downloads.is_some(),
+            "{} has downloads",
+            manifest.id
+        );
+    }
+}
+
+#[test]
+fn test_synthetic_legacy_manifest_uses_fallbacks() {
+    // not a real version, only what the oldest hand-made jsons carry
+    let manifest: VersionManifest = serde_json::from_value(json!({
+        "id": "synthetic-legacy",
+        "time": "2013-10-25T13:00:00+0000",
+        "releaseTime": "2013-10-25T13:00:00+0000",
+        "type": "release",
+        "mainClass": "net.minecraft.client.main.Main",
+        "minecraftArguments": "--username ${auth_player_name} --gameDir ${game_directory}",
+        "assetIndex": {
+            "id": "legacy",
+            "sha1": "",
+            "size": 0,
+            "totalSize": 0,
+            "url": "https://piston-meta.mojang.com/v1/packages/legacy.json"
+        },
+        "libraries": []
+    }))
+    .unwrap();
+
+    let manifest = to_launch_manifest(manifest);
+
+    assert_eq!(manifest.java_version.major_version, 8);
+    assert_eq!(manifest.java_version.component, "jre-legacy");
+    assert!(manifest.logging.client.is_none());
+    assert!(
+        manifest.downloads.is_none(),
+        "Only the local jar can be used"
+    );
+    assert!(matches!(manifest.arguments, ArgumentsInfo::Legacy(_)));
+}
+
+#[test]
+fn test_old_versions_without_logging() {
+    let manifest = to_launch_manifest(parse_manifest(include_str!(
+        "../../tests/fixtures/manifests/a1.0.4.json"
+    )));
+
+    assert!(manifest.logging.client.is_none());
+    assert!(matches!(manifest.arguments, ArgumentsInfo::Legacy(_)));
+}
+
+#[test]
+fn test_modern_version_keeps_logging_and_arguments() {
+    let manifest = to_launch_manifest(parse_manifest(include_str!(
+        "../../tests/fixtures/manifests/1.20.4.json"
+    )));
+
+    assert!(manifest.logging.client.is_some());
+    assert!(matches!(manifest.arguments, ArgumentsInfo::Modern(_)));
+    assert_eq!(manifest.compliance_level, 1);
+}
+
+#[test]
+fn test_legacy_forge_merged_with_vanilla() {
+    let mut manifest = parse_manifest(include_str!("../../tests/fixtures/manifests/1.7.10.json"));
+    let forge = parse_manifest(include_str!(
+        "../../tests/fixtures/manifests/1.7.10-Forge10.13.4.1614-1.7.10.json"
+    ));
+    manifest.merge_with(&forge);
+
+    let manifest = to_launch_manifest(manifest);
+
+    assert_eq!(manifest.id, "1.7.10-Forge10.13.4.1614-1.7.10");
+    assert_eq!(manifest.main_class, "net.minecraft.launchwrapper.Launch");
+    assert_eq!(manifest.java_version.major_version, 8);
+    assert_eq!(manifest.assets, "1.7.10");
+    assert!(
+        manifest
+            .libraries
+            .iter()
+            .any(|l| l.name == "net.minecraftforge:forge:1.7.10-10.13.4.1614-1.7.10")
+    );
+    let ArgumentsInfo::Legacy(arguments) = manifest.arguments else {
+        panic!("Forge 1.7.10 should use legacy arguments");
+    };
+    assert!(arguments.contains("FMLTweaker"));
+}
+
+#[test]
+fn test_hand_made_manifest_uses_fallbacks() {
+    let manifest = to_launch_manifest(parse_manifest(include_str!(
+        "../../tests/fixtures/manifests/hand-made.json"
+    )));
+
+    assert!(manifest.downloads.is_none());
+    assert!(manifest.logging.client.is_none());
+    assert_eq!(
+        manifest.assets, "17",
+        "Assets should fall back to asset index id"
+    );
+    assert_eq!(manifest.compliance_level, 0);
+    assert_eq!(manifest.java_version.major_version, 21);
+    assert_eq!(manifest.java_version.component, "java-runtime-delta");
+}
+
+#[test]
+fn test_manifest_without_arguments_is_rejected() {
+    let mut manifest = parse_manifest(include_str!("../../tests/fixtures/manifests/b1.7.3.json"));
+    manifest.minecraft_arguments = None;
+
+    assert!(LaunchVersionManifest::try_from(manifest).is_err());
+}
+
+fn native_libraries() -> Vec<Library> {
+    serde_json::from_value(json!([
+        {
+            "name": "org.lwjgl.lwjgl:lwjgl-platform:2.9.4-nightly-20150209",
+            "natives": {
+                "linux": "natives-linux",
+                "osx": "natives-osx",
+                "windows": "natives-windows-${arch}"
+            },
+            "extract": { "exclude": ["META-INF/"] }
+        },
+        {
+            "name": "ca.weblounge:jinput-platform:2.0.5",
+            "natives": { "linux": "natives-linux", "linux-arm64": "natives-linux-arm64" },
+            "downloads": {
+                "classifiers": {
+                    "natives-linux-arm64": {
+                        "path": "jinput/jinput-platform-arm64.jar",
+                        "sha1": "",
+                        "size": 0,
+                        "url": ""
+                    }
+                }
+            }
+        },
+        {
+            "name": "org.lwjgl.lwjgl:lwjgl-platform:2.9.2-nightly-20140822",
+            "natives": { "osx": "natives-osx" },
+            "rules": [{ "action": "allow", "os": { "name": "osx" } }]
+        }
+    ]))
+    .unwrap()
+}
+
+fn native_jar_names(os_type: Type, arch: &str) -> Vec<String> {
+    let rule_context = RuleContext {
+        os_info: mock_info(os_type, "1.0", arch),
+        user_features: HashMap::new(),
+    };
+    let libraries_dir = AbsPathBuf::new("/mc/libraries").unwrap();
+    collect_native_jars(&native_libraries(), &libraries_dir, &rule_context)
+        .into_iter()
+        .map(|jar| jar.name)
+        .collect()
+}
+
+#[test]
+fn test_native_classifier_per_os_and_arch() {
+    assert_eq!(
+        native_jar_names(Type::Windows, "x86_64"),
+        ["org.lwjgl.lwjgl:lwjgl-platform:2.9.4-nightly-20150209:natives-windows-64"]
+    );
+    assert_eq!(
+        native_jar_names(Type::Linux, "x86_64"),
+        [
+            "org.lwjgl.lwjgl:lwjgl-platform:2.9.4-nightly-20150209:natives-linux",
+            "ca.weblounge:jinput-platform:2.0.5:natives-linux"
+        ]
+    );
+    assert_eq!(
+        native_jar_names(Type::Linux, "aarch64")[1],
+        "ca.weblounge:jinput-platform:2.0.5:natives-linux-arm64"
+    );
+    assert_eq!(native_jar_names(Type::Macos, "x86_64").len(), 2);
+}
+
+fn write_jar(path: &Path, files: &[(&str, &[u8])]) {
+    let mut writer = ZipWriter::new(fs::File::create(path).unwrap());
+    for (name, content) in files {
+        writer
+            .start_file(*name, SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(content).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+#[test]
+fn test_extract_natives_honors_exclude() {
+    let root = TempDir::new().unwrap();
+    let jar_path = AbsPathBuf::new(&root.path().join("natives-linux.jar")).unwrap();
+    write_jar(
+        &jar_path,
+        &[
+            ("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0"),
+            ("liblwjgl64.so", b"lwjgl"),
+            ("linux/libjinput.so", b"jinput"),
+            ("../escape.so", b"evil"),
+        ],
+    );
+    let natives_dir = root.path().join("natives");
+    let jar = NativeJar {
+        name: "lwjgl-platform".to_string(),
+        path: jar_path,
+        exclude: vec!["META-INF/".to_string()],
+    };
+
+    extract_natives(&[jar], &natives_dir).unwrap();
+
+    assert_eq!(
+        fs::read(natives_dir.join("liblwjgl64.so")).unwrap(),
+        b"lwjgl"
+    );
+    assert_eq!(
+        fs::read(natives_dir.join("linux/libjinput.so")).unwrap(),
+        b"jinput"
+    );
+    assert!(!natives_dir.join("META-INF").exists());
+    assert!(!root.path().join("escape.so").exists());
+}
+
+#[test]
+fn test_remove_unused_natives_keeps_running_games() {
+    let root = TempDir::new().unwrap();
+    let running = Uuid::new_v4().to_string();
+    let stale = Uuid::new_v4().to_string();
+    fs::create_dir_all(root.path().join(&running)).unwrap();
+    fs::write(root.path().join(&running).join("liblwjgl.so"), b"lwjgl").unwrap();
+    fs::create_dir_all(root.path().join(&stale)).unwrap();
+    fs::write(root.path().join(&stale).join("liblwjgl.so"), b"lwjgl").unwrap();
+    fs::write(root.path().join("liblwjgl64.so"), b"shared layout").unwrap();
+
+    remove_unused_natives(root.path(), &HashSet::from([running.clone()])).unwrap();
+
+    assert!(root.path().join(&running).join("liblwjgl.so").exists());
+    assert!(!root.path().join(&stale).exists());
+    assert!(!root.path().join("liblwjgl64.so").exists());
+    assert!(remove_unused_natives(&root.path().join("missing"), &HashSet::new()).is_ok());
+}
@@ -0,0 +1,367 @@
+use crate::game_resolver::VersionManifest;
+use crate::game_resolver::models::ArgumentValue;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Hash, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ModLoaderKind {
+    Forge,
+    NeoForge,
+    Fabric,
+    Quilt,
+    OptiFine,
+    LiteLoader,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ModLoader {
+    pub kind: ModLoaderKind,
+    // unknown when only the main class or a tweaker gives the loader away
+    pub version: Option<String>,
+}
+
+/// Infers the mod loaders of a merged manifest from its libraries, arguments and main class.
+pub fn detect_mod_loaders(manifest: &VersionManifest) -> Vec<ModLoader> {
+    let mut loaders: Vec<ModLoader> = vec![];
+    for library in &manifest.libraries {
+        if let Some((kind, version)) = library_loader(&library.name) {
+            add(&mut loaders, kind, Some(version));
+        }
+    }
+
+    let game_arguments = game_arguments(manifest);
+    let mut forge_arguments = (None, None);
+    for pair in game_arguments.windows(2) {
+        let value = pair[1].to_string();
+        match pair[0] {
+            "--fml.forgeVersion" => forge_arguments.0 = Some(value),
+            "--fml.forgeGroup" => forge_arguments.1 = Some(value),
+            "--fml.neoForgeVersion" => add(&mut loaders, ModLoaderKind::NeoForge, Some(value)),
+            "--tweakClass" => {
+                if let Some(kind) = tweaker_loader(&value) {
+                    add(&mut loaders, kind, None);
+                }
+            }
+            _ => {}
+        }
+    }
+    // neoforge for 1.20.1 still passes its version as a forge version
+    if let (Some(version), group) = forge_arguments {
+        if group.as_deref() == Some("net.neoforged") {
+            add(&mut loaders, ModLoaderKind::NeoForge, Some(version));
+        } else if !has_loader(&loaders, ModLoaderKind::NeoForge) {
+            add(&mut loaders, ModLoaderKind::Forge, Some(version));
+        }
+    }
+
+    if let Some(kind) = main_class_loader(&manifest.main_class) {
+        // both forges start through the same bootstrap launcher
+        let is_neoforge =
+            kind == ModLoaderKind::Forge && has_loader(&loaders, ModLoaderKind::NeoForge);
+        if !is_neoforge {
+            add(&mut loaders, kind, None);
+        }
+    }
+    loaders
+}
+
+/// Reads the game version a version is built on from the data inside its json, so a
+/// renamed or merged version still reports it.
+pub fn detect_game_version(manifest: &VersionManifest) -> Option<String> {
+    if let Some(client_version) = &manifest.client_version {
+        return Some(client_version.clone());
+    }
+    let game_arguments = game_arguments(manifest);
+    if let Some(pair) = game_arguments
+        .windows(2)
+        .find(|pair| pair[0] == "--fml.mcVersion")
+    {
+        return Some(pair[1].to_string());
+    }
+    manifest
+        .libraries
+        .iter()
+        .find_map(|library| library_game_version(&library.name))
+}
+
+fn has_loader(loaders: &[ModLoader], kind: ModLoaderKind) -> bool {
+    loaders.iter().any(|loader| loader.kind == kind)
+}
+
+fn add(loaders: &mut Vec<ModLoader>, kind: ModLoaderKind, version: Option<String>) {
+    match loaders.iter_mut().find(|loader| loader.kind == kind) {
+        Some(loader) => {
+            if loader.version.is_none() {
+                loader.version = version;
+            }
+        }
+        None => loaders.push(ModLoader { kind, version }),
+    }
+}
+
+fn library_loader(name: &str) -> Option<(ModLoaderKind, String)> {
+    let mut parts = name.split('@').next()?.split(':');
+    let (group, artifact, version) = (parts.next()?, parts.next()?, parts.next()?);
+    let loader = match (group, artifact) {
+        ("net.fabricmc", "fabric-loader") => (ModLoaderKind::Fabric, version.to_string()),
+        ("org.quiltmc", "quilt-loader") => (ModLoaderKind::Quilt, version.to_string()),
+        ("net.neoforged", "neoforge") => (ModLoaderKind::NeoForge, version.to_string()),
+        // neoforge for 1.20.1 kept the forge artifact name
+        ("net.neoforged", "forge") => (ModLoaderKind::NeoForge, forge_version(version)),
+        ("net.minecraftforge", "forge" | "minecraftforge") => {
+            (ModLoaderKind::Forge, forge_version(version))
+        }
+        ("optifine", "OptiFine") => {
+            let edition = version
+                .split_once('_')
+                .map_or(version, |(_, edition)| edition);
+            (ModLoaderKind::OptiFine, edition.to_string())
+        }
+        ("com.mumfrey", "liteloader") => (ModLoaderKind::LiteLoader, version.to_string()),
+        _ => return None,
+    };
+    Some(loader)
+}
+
+fn library_game_version(name: &str) -> Option<String> {
+    let mut parts = name.split('@').next()?.split(':');
+    let (group, artifact, version) = (parts.next()?, parts.next()?, parts.next()?);
+    let game_version = match (group, artifact) {
+        ("net.fabricmc", "intermediary") | ("org.quiltmc", "hashed") => version,
+        ("net.minecraftforge" | "net.neoforged", "forge")
+        | ("net.minecraftforge", "minecraftforge")
+        | ("com.mumfrey", "liteloader") => version.split('-').next()?,
+        ("optifine", "OptiFine") => version.split('_').next()?,
+        _ => return None,
+    };
+    // forge before 1.6 carries no game version
+    let is_game_version = game_version.starts_with(|c: char| c.is_ascii_digit())
+        && game_version.contains('.')
+        && game_version.split('.').count() <= 3;
+    is_game_version.then(|| game_version.to_string())
+}
+
+// `<game>-<forge>[-<branch>]`, or a bare forge version before 1.6
+fn forge_version(version: &str) -> String {
+    let segments: Vec<&str> = version.split('-').collect();
+    segments.get(1).unwrap_or(&version).to_string()
+}
+
+fn tweaker_loader(class: &str) -> Option<ModLoaderKind> {
+    match class {
+        "net.minecraftforge.fml.common.launcher.FMLTweaker"
+        | "cpw.mods.fml.common.launcher.FMLTweaker" => Some(ModLoaderKind::Forge),
+        "optifine.OptiFineTweaker" | "optifine.OptiFineForgeTweaker" => {
+            Some(ModLoaderKind::OptiFine)
+        }
+        "com.mumfrey.liteloader.launch.LiteLoaderTweaker" => Some(ModLoaderKind::LiteLoader),
+        _ => None,
+    }
+}
+
+fn main_class_loader(main_class: &str) -> Option<ModLoaderKind> {
+    if main_class.starts_with("net.fabricmc.") {
+        Some(ModLoaderKind::Fabric)
+    } else if main_class.starts_with("org.quiltmc.") {
+        Some(ModLoaderKind::Quilt)
+    } else if main_class.starts_with("cpw.mods.") || main_class.starts_with("net.minecraftforge.") {
+        Some(ModLoaderKind::Forge)
+    } else {
+        None
+    }
+}
+
+fn game_arguments(manifest: &VersionManifest) -> Vec<&str> {
+    let mut arguments: Vec<&str> = manifest
+        .minecraft_arguments
+        .as_deref()
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect();
+    if let Some(modern) = &manifest.arguments {
+        arguments.extend(modern.game.iter().filter_map(|argument| match argument {
+            ArgumentValue::Simple(value) => Some(value.as_str()),
+            ArgumentValue::Complex { .. } => None,
+        }));
+    }
+    arguments
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn manifest(main_class: &str, libraries: &[&str], extra: Value) -> VersionManifest {
+        let mut manifest = json!({
+            "id": "test",
+            "time": "",
+            "releaseTime": "",
+            "type": "release",
+            "mainClass": main_class,
+            "libraries": libraries.iter().map(|name| json!({ "name": name })).collect::<Vec<_>>()
+        });
+        if let (Some(manifest), Some(extra)) = (manifest.as_object_mut(), extra.as_object()) {
+            manifest.extend(extra.clone());
+        }
+        serde_json::from_value(manifest).unwrap()
+    }
+
+    fn loader(kind: ModLoaderKind, version: Option<&str>) -> ModLoader {
+        ModLoader {
+            kind,
+            version: version.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_detects_fabric_and_quilt_from_libraries() {
+        let fabric = manifest(
+            "net.fabricmc.loader.impl.launch.knot.KnotClient",
+            &[
+                "net.fabricmc:intermediary:1.20.4",
+                "net.fabricmc:fabric-loader:0.16.9",
+            ],
+            json!({}),
+        );
+        let quilt = manifest(
+            "org.quiltmc.loader.impl.launch.knot.KnotClient",
+            &[
+                "net.fabricmc:intermediary:1.20.4",
+                "org.quiltmc:quilt-loader:0.27.1",
+            ],
+            json!({}),
+        );
+
+        assert_eq!(
+            detect_mod_loaders(&fabric),
+            vec![loader(ModLoaderKind::Fabric, Some("0.16.9"))]
+        );
+        assert_eq!(
+            detect_mod_loaders(&quilt),
+            vec![loader(ModLoaderKind::Quilt, Some("0.27.1"))]
+        );
+    }
+
+    #[test]
+    fn test_tells_neoforge_from_forge() {
+        let forge = manifest(
+            "cpw.mods.bootstraplauncher.BootstrapLauncher",
+            &["net.minecraftforge:fmlloader:1.20.4-49.0.30"],
+            json!({ "arguments": { "game": ["--fml.forgeVersion", "49.0.30", "--launchTarget", "forgeclient"] } }),
+        );
+        let neoforge = manifest(
+            "cpw.mods.bootstraplauncher.BootstrapLauncher",
+            &["net.neoforged.fancymodloader:loader:4.0.24"],
+            json!({ "arguments": { "game": ["--fml.neoForgeVersion", "21.1.77"] } }),
+        );
+        let neoforge_1_20_1_arguments = json!({ "arguments": { "game": [
+            "--fml.forgeVersion", "47.1.106", "--fml.mcVersion", "1.20.1",
+            "--fml.forgeGroup", "net.neoforged", "--fml.mcpVersion", "20230612.114412"
+        ] } });
+        let neoforge_1_20_1 = manifest(
+            "cpw.mods.bootstraplauncher.BootstrapLauncher",
+            &["net.neoforged:forge:1.20.1-47.1.106:universal"],
+            neoforge_1_20_1_arguments.clone(),
+        );
+        let neoforge_1_20_1_without_library = manifest(
+            "cpw.mods.bootstraplauncher.BootstrapLauncher",
+            &[],
+            neoforge_1_20_1_arguments,
+        );
+
+        assert_eq!(
+            detect_mod_loaders(&forge),
+            vec![loader(ModLoaderKind::Forge, Some("49.0.30"))]
+        );
+        assert_eq!(
+            detect_mod_loaders(&neoforge),
+            vec![loader(ModLoaderKind::NeoForge, Some("21.1.77"))]
+        );
+        assert_eq!(
+            detect_mod_loaders(&neoforge_1_20_1),
+            vec![loader(ModLoaderKind::NeoForge, Some("47.1.106"))]
+        );
+        assert_eq!(
+            detect_mod_loaders(&neoforge_1_20_1_without_library),
+            vec![loader(ModLoaderKind::NeoForge, Some("47.1.106"))]
+        );
+    }
+
+    #[test]
+    fn test_detect_game_version_from_json_data() {
+        let merged_forge = manifest(
+            "net.minecraft.launchwrapper.Launch",
+            &["net.minecraftforge:forge:1.12.2-14.23.5.2859"],
+            json!({}),
+        );
+        let modern_forge = manifest(
+            "cpw.mods.bootstraplauncher.BootstrapLauncher",
+            &[],
+            json!({ "arguments": { "game": ["--fml.mcVersion", "1.20.4"] } }),
+        );
+        let fabric = manifest(
+            "net.fabricmc.loader.impl.launch.knot.KnotClient",
+            &[],
+            json!({ "clientVersion": "1.20.4" }),
+        );
+
+        assert_eq!(
+            detect_game_version(&merged_forge).as_deref(),
+            Some("1.12.2")
+        );
+        assert_eq!(
+            detect_game_version(&modern_forge).as_deref(),
+            Some("1.20.4")
+        );
+        assert_eq!(detect_game_version(&fabric).as_deref(), Some("1.20.4"));
+        assert_eq!(
+            detect_game_version(&manifest(
+                "net.minecraft.launchwrapper.Launch",
+                &["net.minecraftforge:minecraftforge:9.11.1.965"],
+                json!({}),
+            )),
+            None
+        );
+    }
+
+    #[test]
+    fn test_detects_launchwrapper_loaders_from_tweakers() {
+        let legacy = manifest(
+            "net.minecraft.launchwrapper.Launch",
+            &[
+                "com.mumfrey:liteloader:1.12.2-SNAPSHOT",
+                "net.minecraftforge:forge:1.7.10-10.13.4.1614-1.7.10",
+                "optifine:OptiFine:1.7.10_HD_U_E7",
+            ],
+            json!({
+                "minecraftArguments": "--username ${auth_player_name} --tweakClass optifine.OptiFineForgeTweaker --tweakClass cpw.mods.fml.common.launcher.FMLTweaker"
+            }),
+        );
+        let optifine_only = manifest(
+            "net.minecraft.launchwrapper.Launch",
+            &[],
+            json!({ "arguments": { "game": ["--tweakClass", "optifine.OptiFineTweaker"] } }),
+        );
+
+        assert_eq!(
+            detect_mod_loaders(&legacy),
+            vec![
+                loader(ModLoaderKind::LiteLoader, Some("1.12.2-SNAPSHOT")),
+                loader(ModLoaderKind::Forge, Some("10.13.4.1614")),
+                loader(ModLoaderKind::OptiFine, Some("HD_U_E7")),
+            ]
+        );
+        assert_eq!(
+            detect_mod_loaders(&optifine_only),
+            vec![loader(ModLoaderKind::OptiFine, None)]
+        );
+        assert!(
+            detect_mod_loaders(&manifest("net.minecraft.client.main.Main", &[], json!({})))
+                .is_empty()
+        );
+    }
+}
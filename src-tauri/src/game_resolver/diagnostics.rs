@@ -0,0 +1,206 @@
+use crate::i18n::locales::{I18nKeys, t, t_args};
+use fluent_templates::fluent_bundle::FluentValue;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenVersion {
+    pub id: String,
+    pub reason: BrokenReason,
+    // shown on the library page as is, in the language of the launcher
+    pub message: String,
+    pub repair_action: RepairAction,
+}
+
+impl BrokenVersion {
+    pub fn new(id: String, reason: BrokenReason) -> Self {
+        Self {
+            id,
+            message: reason.localized_message(),
+            repair_action: reason.repair_action(),
+            reason,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Error)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum BrokenReason {
+    #[error("Failed to read version json of '{version}': {message}")]
+    Unreadable { version: String, message: String },
+    #[error("Invalid version json of '{version}' at line {line}, column {column}: {message}")]
+    InvalidJson {
+        version: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    #[error("Version id '{found}' does not match directory name '{expected}'")]
+    IdMismatch { expected: String, found: String },
+    #[error("Version '{child}' inherits from '{parent}', which is not installed")]
+    MissingParent { child: String, parent: String },
+    #[error("Circular inheritance detected: {}", .chain.join(" -> "))]
+    CircularInheritance { chain: Vec<String> },
+    #[error("Game jar not found at {path}")]
+    MissingJar { path: String },
+    #[error("Version manifest is missing launch fields: {}", .fields.join(", "))]
+    IncompleteManifest { fields: Vec<String> },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum RepairAction {
+    Reinstall,
+    RedownloadJar,
+    InstallParent,
+    FixVersionId,
+}
+
+impl BrokenReason {
+    pub fn i18n_key(&self) -> I18nKeys {
+        match self {
+            BrokenReason::Unreadable { .. } => I18nKeys::BrokenVersionUnreadable,
+            BrokenReason::InvalidJson { .. } => I18nKeys::BrokenVersionInvalidJson,
+            BrokenReason::IdMismatch { .. } => I18nKeys::BrokenVersionIdMismatch,
+            BrokenReason::MissingParent { .. } => I18nKeys::BrokenVersionMissingParent,
+            BrokenReason::CircularInheritance { .. } => I18nKeys::BrokenVersionCircularInheritance,
+            BrokenReason::MissingJar { .. } => I18nKeys::BrokenVersionMissingJar,
+            BrokenReason::IncompleteManifest { .. } => I18nKeys::BrokenVersionIncompleteManifest,
+        }
+    }
+
+    pub fn i18n_args(&self) -> HashMap<Cow<'static, str>, FluentValue<'static>> {
+        let args: Vec<(&'static str, FluentValue<'static>)> = match self {
+            BrokenReason::Unreadable { message, .. } => vec![("message", message.clone().into())],
+            BrokenReason::InvalidJson { line, column, .. } => {
+                vec![("line", (*line).into()), ("column", (*column).into())]
+            }
+            BrokenReason::IdMismatch { expected, found } => vec![
+                ("expected", expected.clone().into()),
+                ("found", found.clone().into()),
+            ],
+            BrokenReason::MissingParent { parent, .. } => vec![("parent", parent.clone().into())],
+            BrokenReason::CircularInheritance { chain } => {
+                vec![("chain", chain.join(" -> ").into())]
+            }
+            BrokenReason::MissingJar { path } => vec![("path", path.clone().into())],
+            BrokenReason::IncompleteManifest { fields } => {
+                vec![("fields", fields.join(", ").into())]
+            }
+        };
+        args.into_iter()
+            .map(|(k, v)| (Cow::Borrowed(k), v))
+            .collect()
+    }
+
+    pub fn localized_message(&self) -> String {
+        t_args(self.i18n_key(), &self.i18n_args())
+    }
+
+    pub fn repair_action(&self) -> RepairAction {
+        match self {
+            BrokenReason::MissingJar { .. } => RepairAction::RedownloadJar,
+            BrokenReason::MissingParent { .. } => RepairAction::InstallParent,
+            BrokenReason::IdMismatch { .. } => RepairAction::FixVersionId,
+            BrokenReason::Unreadable { .. }
+            | BrokenReason::InvalidJson { .. }
+            | BrokenReason::CircularInheritance { .. }
+            | BrokenReason::IncompleteManifest { .. } => RepairAction::Reinstall,
+        }
+    }
+}
+
+impl RepairAction {
+    pub fn i18n_key(&self) -> I18nKeys {
+        match self {
+            RepairAction::Reinstall => I18nKeys::RepairActionReinstall,
+            RepairAction::RedownloadJar => I18nKeys::RepairActionRedownloadJar,
+            RepairAction::InstallParent => I18nKeys::RepairActionInstallParent,
+            RepairAction::FixVersionId => I18nKeys::RepairActionFixVersionId,
+        }
+    }
+
+    pub fn localized_message(&self) -> String {
+        t(self.i18n_key())
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repair_action_mapping() {
+        let missing_parent = BrokenReason::MissingParent {
+            child: "fabric".into(),
+            parent: "1.20.1".into(),
+        };
+        let missing_jar = BrokenReason::MissingJar {
+            path: "/versions/1.20.1/1.20.1.jar".into(),
+        };
+        let id_mismatch = BrokenReason::IdMismatch {
+            expected: "renamed".into(),
+            found: "1.20.1".into(),
+        };
+
+        assert_eq!(missing_parent.repair_action(), RepairAction::InstallParent);
+        assert_eq!(missing_jar.repair_action(), RepairAction::RedownloadJar);
+        assert_eq!(id_mismatch.repair_action(), RepairAction::FixVersionId);
+    }
+
+    #[test]
+    fn test_localized_message_contains_args() {
+        let reason = BrokenReason::InvalidJson {
+            version: "broken".into(),
+            line: 12,
+            column: 34,
+            message: "EOF while parsing".into(),
+        };
+
+        let message = reason.localized_message();
+
+        assert!(message.contains("12"), "Line should be shown: {message}");
+        assert!(message.contains("34"), "Column should be shown: {message}");
+    }
+
+    #[test]
+    fn test_serialize_reason_with_kind_tag() {
+        let reason = BrokenReason::MissingParent {
+            child: "fabric".into(),
+            parent: "1.20.1".into(),
+        };
+
+        let value = serde_json::to_value(&reason).unwrap();
+
+        assert_eq!(value["kind"], "missingParent");
+        assert_eq!(value["parent"], "1.20.1");
+    }
+
+    #[test]
+    fn test_serialize_broken_version_with_message_and_repair_action() {
+        let broken = BrokenVersion::new(
+            "fabric".into(),
+            BrokenReason::MissingParent {
+                child: "fabric".into(),
+                parent: "1.20.1".into(),
+            },
+        );
+
+        let value = serde_json::to_value(&broken).unwrap();
+
+        assert_eq!(value["id"], "fabric");
+        assert_eq!(value["reason"]["kind"], "missingParent");
+        assert_eq!(value["repairAction"], "installParent");
+        assert_eq!(value["message"], broken.reason.localized_message());
+        assert!(!value["message"].as_str().unwrap().is_empty());
+    }
+}
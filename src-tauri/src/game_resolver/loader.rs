@@ -1,9 +1,14 @@
 use crate::constants::minecraft_dir::VERSIONS_DIR_NAME;
+use crate::game_resolver::detection::{detect_game_version, detect_mod_loaders};
+use crate::game_resolver::diagnostics::BrokenReason::{
+    CircularInheritance, IdMismatch, IncompleteManifest, InvalidJson, MissingJar, MissingParent,
+    Unreadable,
+};
+use crate::game_resolver::diagnostics::{BrokenReason, BrokenVersion};
 use crate::game_resolver::models::VersionData::{Broken, Normal};
-use crate::game_resolver::models::{VersionData, VersionManifest};
+use crate::game_resolver::models::{InstalledVersion, VersionData, VersionManifest};
 use crate::game_resolver::scanner::VersionMetadata;
 use crate::utils::abs_path_buf::AbsPathBuf;
-use anyhow::Result;
 use async_trait::async_trait;
 use log::warn;
 use tokio::fs;
@@ -26,6 +31,13 @@ impl FileSystemVersionLoader {
             .join(version_id.clone())
             .join(format!("{version_id}.json"))
     }
+
+    fn resolve_jar_path(&self, minecraft_folder: AbsPathBuf, version_id: String) -> AbsPathBuf {
+        minecraft_folder
+            .join(VERSIONS_DIR_NAME)
+            .join(version_id.clone())
+            .join(format!("{version_id}.jar"))
+    }
 }
 
 #[async_trait]
@@ -36,12 +48,12 @@ impl VersionLoader for FileSystemVersionLoader {
         metadata: VersionMetadata,
     ) -> VersionData {
         let id = metadata.id.clone();
-        let resolved = self.load_manifest(minecraft_folder, id.clone()).await;
+        let resolved = self.resolve_manifest(minecraft_folder, id.clone()).await;
 
         match resolved {
-            Err(e) => {
-                warn!("Failed to resolve minecraft version {id}: {e:?}");
-                Broken(id)
+            Err(reason) => {
+                warn!("Failed to resolve minecraft version {id}: {reason}");
+                Broken(BrokenVersion::new(id, reason))
             }
             Ok(data) => Normal(data),
         }
@@ -49,14 +61,125 @@ impl VersionLoader for FileSystemVersionLoader {
 }
 
 impl FileSystemVersionLoader {
+    async fn resolve_manifest(
+        &self,
+        root_dir: AbsPathBuf,
+        version_id: String,
+    ) -> Result<InstalledVersion, BrokenReason> {
+        let chain = self
+            .load_inheritance_chain(root_dir.clone(), version_id)
+            .await?;
+        self.verify_game_jar(root_dir, &chain).await?;
+
+        let root_id = chain.last().map(|root| root.id.clone()).unwrap_or_default();
+        let manifest = Self::merge_chain(chain);
+        let missing_fields = manifest.missing_launch_fields();
+        if !missing_fields.is_empty() {
+            return Err(IncompleteManifest {
+                fields: missing_fields,
+            });
+        }
+
+        // the root id is only a folder name, which may have been renamed
+        let game_version = detect_game_version(&manifest).unwrap_or(root_id);
+        let mod_loaders = detect_mod_loaders(&manifest);
+        Ok(InstalledVersion {
+            manifest,
+            game_version,
+            mod_loaders,
+        })
+    }
+
+    fn merge_chain(mut chain: Vec<VersionManifest>) -> VersionManifest {
+        let mut resolved = chain
+            .pop()
+            .expect("Internal Error: Inheritance chain should never be empty"); // this should never happen
+        while let Some(child) = chain.pop() {
+            resolved.merge_with(&child);
+        }
+        resolved
+    }
+
+    async fn load_inheritance_chain(
+        &self,
+        root_dir: AbsPathBuf,
+        version_id: String,
+    ) -> Result<Vec<VersionManifest>, BrokenReason> {
+        let mut chain = Vec::new();
+        let mut visited: Vec<String> = Vec::new();
+        let mut next_id = Some(version_id);
+
+        while let Some(id) = next_id {
+            if visited.contains(&id) {
+                visited.push(id);
+                return Err(CircularInheritance { chain: visited });
+            }
+
+            let json_path = self.resolve_json_path(root_dir.clone(), id.clone());
+            if let Some(child) = visited.last()
+                && !fs::try_exists(&json_path).await.unwrap_or(false)
+            {
+                return Err(MissingParent {
+                    child: child.clone(),
+                    parent: id,
+                });
+            }
+
+            let manifest = self.load_manifest(root_dir.clone(), id.clone()).await?;
+            next_id = manifest.inherits_from.clone();
+            visited.push(id);
+            chain.push(manifest);
+        }
+
+        Ok(chain)
+    }
+
+    async fn verify_game_jar(
+        &self,
+        root_dir: AbsPathBuf,
+        chain: &[VersionManifest],
+    ) -> Result<(), BrokenReason> {
+        for manifest in chain {
+            let jar_path = self.resolve_jar_path(root_dir.clone(), manifest.id.clone());
+            if fs::try_exists(&jar_path).await.unwrap_or(false) {
+                return Ok(());
+            }
+        }
+
+        let root_id = chain.last().map(|m| m.id.clone()).unwrap_or_default();
+        let path = self.resolve_jar_path(root_dir, root_id);
+        Err(MissingJar {
+            path: path.to_string_lossy().to_string(),
+        })
+    }
+
     async fn load_manifest(
         &self,
         root_dir: AbsPathBuf,
         version_id: String,
-    ) -> Result<VersionManifest> {
-        let json_path = self.resolve_json_path(root_dir, version_id);
-        let content = fs::read_to_string(&json_path).await?;
-        let manifest: VersionManifest = serde_json::from_str(&content)?;
+    ) -> Result<VersionManifest, BrokenReason> {
+        let json_path = self.resolve_json_path(root_dir, version_id.clone());
+        let content = fs::read_to_string(&json_path)
+            .await
+            .map_err(|e| Unreadable {
+                version: version_id.clone(),
+                message: e.to_string(),
+            })?;
+        let manifest: VersionManifest =
+            serde_json::from_str(&content).map_err(|e| InvalidJson {
+                version: version_id.clone(),
+                line: e.line(),
+                column: e.column(),
+                message: e.to_string(),
+            })?;
+
+        if manifest.id != version_id {
+            return Err(IdMismatch {
+                expected: version_id,
+                found: manifest.id,
+            });
+        }
+
         Ok(manifest)
     }
 }
@@ -113,4 +236,208 @@ mod tests {
             result
         );
     }
+
+    async fn write_version(root_path: &AbsPathBuf, id: &str, inherits_from: Option<&str>) {
+        let version_dir = root_path.join(VERSIONS_DIR_NAME).join(id);
+        fs::create_dir_all(&version_dir).await.unwrap();
+
+        let inherits_from = inherits_from
+            .map(|parent| format!(r#""inheritsFrom": "{parent}","#))
+            .unwrap_or_default();
+        let content = format!(
+            r#"{{
+                "id": "{id}",
+                {inherits_from}
+                "time": "",
+                "releaseTime": "",
+                "type": "release",
+                "mainClass": "{id}.Main"
+            }}"#
+        );
+        fs::write(version_dir.join(format!("{id}.json")), content)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resolve_follows_inheritance_chain() {
+        let temp_dir = tempdir().unwrap();
+        let root_path: AbsPathBuf = temp_dir.path().to_path_buf().try_into().unwrap();
+        write_version(&root_path, "vanilla", None).await;
+        write_version(&root_path, "forge", Some("vanilla")).await;
+        write_version(&root_path, "modpack", Some("forge")).await;
+
+        let chain = FileSystemVersionLoader
+            .load_inheritance_chain(root_path, "modpack".to_string())
+            .await
+            .unwrap();
+        let ids: Vec<&str> = chain.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["modpack", "forge", "vanilla"]);
+
+        let resolved = FileSystemVersionLoader::merge_chain(chain);
+        assert_eq!(resolved.id, "modpack");
+        assert_eq!(resolved.main_class, "modpack.Main");
+        assert_eq!(resolved.inherits_from, Some("forge".into()));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_error_missing_parent() {
+        let temp_dir = tempdir().unwrap();
+        let root_path: AbsPathBuf = temp_dir.path().to_path_buf().try_into().unwrap();
+        write_version(&root_path, "fabric", Some("1.20.1")).await;
+
+        let err = FileSystemVersionLoader
+            .resolve_manifest(root_path, "fabric".to_string())
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(
+                &err,
+                MissingParent { child, parent } if child == "fabric" && parent == "1.20.1"
+            ),
+            "Should report the missing parent, but got: {err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_error_circular_inheritance() {
+        let temp_dir = tempdir().unwrap();
+        let root_path: AbsPathBuf = temp_dir.path().to_path_buf().try_into().unwrap();
+        write_version(&root_path, "a", Some("b")).await;
+        write_version(&root_path, "b", Some("c")).await;
+        write_version(&root_path, "c", Some("a")).await;
+
+        let err = FileSystemVersionLoader
+            .resolve_manifest(root_path, "a".to_string())
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(
+                &err,
+                CircularInheritance { chain } if chain == &vec!["a", "b", "c", "a"]
+            ),
+            "Should report the inheritance cycle, but got: {err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_error_json_parse_position() {
+        let temp_dir = tempdir().unwrap();
+        let root_path: AbsPathBuf = temp_dir.path().to_path_buf().try_into().unwrap();
+        let version_dir = root_path.join(VERSIONS_DIR_NAME).join("bad_json");
+        fs::create_dir_all(&version_dir).await.unwrap();
+        fs::write(
+            version_dir.join("bad_json.json"),
+            "{\n  \"id\": \"bad_json\",\n  oops\n}",
+        )
+        .await
+        .unwrap();
+
+        let err = FileSystemVersionLoader
+            .load_manifest(root_path, "bad_json".to_string())
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                InvalidJson {
+                    line: 3,
+                    column: 3,
+                    ..
+                }
+            ),
+            "Should report the error position, but got: {err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_error_id_mismatch() {
+        let temp_dir = tempdir().unwrap();
+        let root_path: AbsPathBuf = temp_dir.path().to_path_buf().try_into().unwrap();
+        write_version(&root_path, "1.20.1", None).await;
+        fs::rename(
+            root_path.join(VERSIONS_DIR_NAME).join("1.20.1"),
+            root_path.join(VERSIONS_DIR_NAME).join("renamed"),
+        )
+        .await
+        .unwrap();
+        fs::rename(
+            root_path
+                .join(VERSIONS_DIR_NAME)
+                .join("renamed")
+                .join("1.20.1.json"),
+            root_path
+                .join(VERSIONS_DIR_NAME)
+                .join("renamed")
+                .join("renamed.json"),
+        )
+        .await
+        .unwrap();
+
+        let err = FileSystemVersionLoader
+            .load_manifest(root_path, "renamed".to_string())
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            IdMismatch {
+                expected: "renamed".into(),
+                found: "1.20.1".into(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_error_missing_jar() {
+        let temp_dir = tempdir().unwrap();
+        let root_path: AbsPathBuf = temp_dir.path().to_path_buf().try_into().unwrap();
+        write_version(&root_path, "vanilla", None).await;
+        write_version(&root_path, "fabric", Some("vanilla")).await;
+
+        let err = FileSystemVersionLoader
+            .resolve_manifest(root_path.clone(), "fabric".to_string())
+            .await
+            .unwrap_err();
+
+        let expected_path = root_path
+            .join(VERSIONS_DIR_NAME)
+            .join("vanilla")
+            .join("vanilla.jar");
+        assert_eq!(
+            err,
+            MissingJar {
+                path: expected_path.to_string_lossy().to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_error_incomplete_manifest() {
+        let temp_dir = tempdir().unwrap();
+        let root_path: AbsPathBuf = temp_dir.path().to_path_buf().try_into().unwrap();
+        write_version(&root_path, "vanilla", None).await;
+        fs::write(
+            root_path
+                .join(VERSIONS_DIR_NAME)
+                .join("vanilla")
+                .join("vanilla.jar"),
+            "",
+        )
+        .await
+        .unwrap();
+
+        let err = FileSystemVersionLoader
+            .resolve_manifest(root_path, "vanilla".to_string())
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(&err, IncompleteManifest { fields } if fields.contains(&"assetIndex".to_string())),
+            "Should report missing launch fields, but got: {err:?}"
+        );
+    }
 }
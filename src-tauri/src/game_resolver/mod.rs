@@ -1,3 +1,5 @@
+pub mod detection;
+pub mod diagnostics;
 pub mod loader;
 pub mod models;
 pub mod resolve;
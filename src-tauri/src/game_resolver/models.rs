@@ -1,3 +1,5 @@
+use crate::game_resolver::detection::ModLoader;
+use crate::game_resolver::diagnostics::BrokenVersion;
 use crate::game_resolver::models::ArgumentValue::Simple;
 use crate::game_resolver::models::ArgumentValueContent::{Multiple, Single};
 use crate::utils::abs_path_buf::AbsPathBuf;
@@ -15,8 +17,20 @@ pub struct MinecraftFolderInfo {
 #[allow(clippy::large_enum_variant)]
 #[serde(untagged)]
 pub enum VersionData {
-    Normal(VersionManifest),
-    Broken(String),
+    Normal(InstalledVersion),
+    Broken(BrokenVersion),
+}
+
+/// A version merged with its parents, and what it was built on.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledVersion {
+    #[serde(flatten)]
+    pub manifest: VersionManifest,
+    // detected from clientVersion, --fml.mcVersion or the loader library, else the id of the
+    // root of the `inheritsFrom` chain
+    pub game_version: String,
+    pub mod_loaders: Vec<ModLoader>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, Eq, PartialEq)]
@@ -46,6 +60,9 @@ pub struct VersionManifest {
     pub version_type: String,
     #[serde(default)]
     pub inherits_from: Option<String>,
+    // written by some third party launchers next to a renamed or merged version
+    #[serde(default)]
+    pub client_version: Option<String>,
     // when this struct modified, remember to update VersionManifest::merge_with if needed
 }
 
@@ -82,6 +99,38 @@ impl VersionManifest {
         if child.downloads.is_some() {
             self.downloads = child.downloads.clone();
         }
+
+        if child.assets.is_some() {
+            self.assets = child.assets.clone();
+        }
+
+        if child.compliance_level.is_some() {
+            self.compliance_level = child.compliance_level;
+        }
+
+        if child.logging.is_some() {
+            self.logging = child.logging.clone();
+        }
+
+        if child.client_version.is_some() {
+            self.client_version = child.client_version.clone();
+        }
+
+        self.inherits_from = child.inherits_from.clone();
+    }
+
+    pub fn missing_launch_fields(&self) -> Vec<String> {
+        [
+            (
+                "arguments",
+                self.arguments.is_none() && self.minecraft_arguments.is_none(),
+            ),
+            ("assetIndex", self.asset_index.is_none()),
+        ]
+        .into_iter()
+        .filter(|(_, missing)| *missing)
+        .map(|(field, _)| field.to_string())
+        .collect()
     }
 }
 
@@ -159,6 +208,18 @@ pub struct Library {
     pub natives: Option<HashMap<String, String>>,
     #[serde(default)]
     pub rules: Option<Vec<Rule>>,
+    #[serde(default)]
+    pub extract: Option<ExtractRules>,
+    // maven repository of libraries without `downloads`, as used by mod loaders
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractRules {
+    #[serde(default)]
+    pub exclude: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
@@ -172,6 +233,8 @@ pub struct LibraryDownloads {
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct DownloadFile {
+    #[serde(default)]
+    pub id: Option<String>,
     pub path: Option<String>,
     pub sha1: String,
     pub size: u64,
@@ -253,6 +316,7 @@ mod tests {
                 manifest: VersionManifest {
                     id: id.into(),
                     inherits_from: None,
+                    client_version: None,
                     time: "2023-01-01T00:00:00Z".into(),
                     release_time: "2023-01-01T00:00:00Z".into(),
                     version_type: "release".into(),
@@ -443,6 +507,7 @@ mod tests {
     #[test]
     fn test_merge_branch_downloads_override() {
         let download_file = DownloadFile {
+            id: None,
             path: Some("path/to/client".into()),
             sha1: "dummy_sha1".into(),
             size: 12345,
@@ -463,6 +528,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_merge_branch_inherited_fields_override() {
+        let mut base = ManifestBuilder::new("base").build();
+        base.assets = Some("1.12".into());
+        base.compliance_level = Some(0);
+
+        let mut child = ManifestBuilder::new("child").build();
+        child.inherits_from = Some("base".into());
+        child.compliance_level = Some(1);
+
+        base.merge_with(&child);
+
+        assert_eq!(base.assets, Some("1.12".into()), "Should keep base assets");
+        assert_eq!(base.compliance_level, Some(1));
+        assert_eq!(base.inherits_from, Some("base".into()));
+    }
+
     #[test]
     fn test_argument_value_into_vec() {
         let single_value = "arg1".to_string();
@@ -1,7 +1,9 @@
 use crate::game_resolver::loader::FileSystemVersionLoader;
+use crate::game_resolver::models::VersionData::{Broken, Normal};
 use crate::game_resolver::models::{MinecraftFolderInfo, MinecraftFolderSettings, VersionData};
-use crate::game_resolver::{FileSystemScanner, VersionLoader, VersionScanner};
+use crate::game_resolver::{FileSystemScanner, VersionLoader, VersionManifest, VersionScanner};
 use crate::utils::abs_path_buf::AbsPathBuf;
+use anyhow::{Result, anyhow};
 use futures::StreamExt;
 use futures::stream;
 
@@ -24,6 +26,21 @@ pub async fn resolve_all_versions_default(minecraft_folder: AbsPathBuf) -> Vec<V
     .await
 }
 
+pub async fn resolve_installed_version(
+    minecraft_folder: AbsPathBuf,
+    version_id: &str,
+) -> Result<VersionManifest> {
+    resolve_all_versions_default(minecraft_folder)
+        .await
+        .into_iter()
+        .find_map(|version| match version {
+            Normal(version) if version.manifest.id == version_id => Some(Ok(version.manifest)),
+            Broken(broken) if broken.id == version_id => Some(Err(anyhow!(broken.reason))),
+            _ => None,
+        })
+        .unwrap_or_else(|| Err(anyhow!("Version '{version_id}' is not installed")))
+}
+
 pub async fn resolve_all_versions<S, L>(
     scanner: &S,
     loader: &L,
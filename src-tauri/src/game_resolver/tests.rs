@@ -2,10 +2,13 @@
 #![cfg(test)]
 
 use crate::constants::minecraft_dir::VERSIONS_DIR_NAME;
+use crate::game_resolver::detection::{ModLoader, ModLoaderKind};
+use crate::game_resolver::diagnostics::BrokenReason;
 use crate::game_resolver::models::VersionData::{Broken, Normal};
 use crate::game_resolver::resolve::resolve_all_versions_default;
 use crate::game_resolver::{FileSystemScanner, VersionScanner};
 use crate::utils::abs_path_buf::AbsPathBuf;
+use serde_json::{Value, json};
 use tempfile::TempDir;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
@@ -39,9 +42,30 @@ impl TestEnvironment {
         file.write_all(json_content.into().as_bytes())
             .await
             .expect("Failed to write json");
+
+        fs::File::create(version_dir.join(format!("{id}.jar")))
+            .await
+            .expect("Failed to create jar file");
     }
 }
 
+fn launchable_manifest(id: &str) -> Value {
+    json!({
+        "id": id,
+        "time": "",
+        "releaseTime": "",
+        "type": "release",
+        "mainClass": "net.minecraft.client.main.Main",
+        "minecraftArguments": "--username ${auth_player_name}",
+        "assetIndex": { "id": "5", "sha1": "", "size": 0, "url": "", "totalSize": 0 },
+        "assets": "5",
+        "complianceLevel": 1,
+        "downloads": { "client": { "sha1": "", "size": 0, "url": "" } },
+        "logging": {},
+        "javaVersion": { "component": "java-runtime-gamma", "majorVersion": 17 }
+    })
+}
+
 #[tokio::test]
 async fn test_version_without_json() {
     let env = TestEnvironment::new().await;
@@ -93,17 +117,8 @@ async fn test_version_with_same_name_dir_instead_of_json() {
 async fn test_resilience_to_broken_json() {
     let env = TestEnvironment::new().await;
 
-    env.add_version(
-        "good",
-        r#"{
-            "id": "good",
-            "time": "",
-            "releaseTime": "",
-            "type": "release",
-            "mainClass": "Main"
-        }"#,
-    )
-    .await;
+    env.add_version("good", launchable_manifest("good").to_string())
+        .await;
     env.add_version(
         "bad",
         r#"{
@@ -125,3 +140,82 @@ async fn test_resilience_to_broken_json() {
         "Bad version should exist as an broken result"
     );
 }
+
+#[tokio::test]
+async fn test_inherited_version_is_merged_with_parent() {
+    let env = TestEnvironment::new().await;
+
+    let mut vanilla = launchable_manifest("1.20.1");
+    vanilla["libraries"] = json!([{ "name": "com.mojang:brigadier:1.1.8" }]);
+    env.add_version("1.20.1", vanilla.to_string()).await;
+    env.add_version(
+        "fabric-loader-0.15.0-1.20.1",
+        r#"{
+            "id": "fabric-loader-0.15.0-1.20.1",
+            "inheritsFrom": "1.20.1",
+            "time": "",
+            "releaseTime": "",
+            "type": "release",
+            "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+            "libraries": [{ "name": "net.fabricmc:fabric-loader:0.15.0" }]
+        }"#,
+    )
+    .await;
+    env.add_version(
+        "orphan",
+        r#"{
+            "id": "orphan",
+            "inheritsFrom": "1.19.4",
+            "time": "",
+            "releaseTime": "",
+            "type": "release",
+            "mainClass": "Main"
+        }"#,
+    )
+    .await;
+
+    let results = resolve_all_versions_default(env.path).await;
+
+    let fabric = results
+        .iter()
+        .find_map(|r| match r {
+            Normal(version) if version.manifest.id == "fabric-loader-0.15.0-1.20.1" => {
+                Some(version)
+            }
+            _ => None,
+        })
+        .expect("Fabric version should be resolved");
+    assert_eq!(
+        fabric.manifest.main_class,
+        "net.fabricmc.loader.impl.launch.knot.KnotClient"
+    );
+    assert_eq!(fabric.manifest.assets, Some("5".into()));
+    assert_eq!(
+        fabric.manifest.libraries.len(),
+        2,
+        "Libraries should be merged"
+    );
+    assert_eq!(fabric.game_version, "1.20.1");
+    assert_eq!(
+        fabric.mod_loaders,
+        vec![ModLoader {
+            kind: ModLoaderKind::Fabric,
+            version: Some("0.15.0".into()),
+        }]
+    );
+
+    let orphan = results
+        .iter()
+        .find_map(|r| match r {
+            Broken(broken) if broken.id == "orphan" => Some(broken),
+            _ => None,
+        })
+        .expect("Version with missing parent should be broken");
+    assert_eq!(
+        orphan.reason,
+        BrokenReason::MissingParent {
+            child: "orphan".into(),
+            parent: "1.19.4".into(),
+        }
+    );
+}
@@ -0,0 +1,175 @@
+use crate::constants::file_system::RUNTIME_DIR_NAME;
+use crate::game_installer::catalog::VersionCatalogClient;
+use crate::game_launcher::models::RuleContext;
+use crate::game_resolver::models::JavaVersion;
+use crate::java_runtime::models::{JavaArch, JavaInstance};
+use crate::java_runtime::mojang::{install_mojang_runtime_task, runtime_platform};
+use crate::java_runtime::scanner::{JavaScanSource, UserPathSource, scan_all, scan_sources};
+use crate::java_runtime::selector::{JavaRequirement, JavaSelection, select_java};
+use crate::java_runtime::vendor_download::{JavaVendor, VendorJdkQuery, install_vendor_jdk_task};
+use crate::scheduler::Scheduler;
+use crate::settings::modules::java::JavaSettings;
+use crate::settings::store::SettingsStore;
+use crate::utils::abs_path_buf::AbsPathBuf;
+use crate::utils::command::CommandResult;
+use crate::utils::dirs::app_dir;
+use anyhow::anyhow;
+use macros::command;
+use reqwest::Client;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::State;
+use tokio::fs;
+
+#[command]
+pub async fn list_java_runtimes(
+    store: State<'_, Arc<SettingsStore>>,
+) -> CommandResult<Vec<JavaInstance>> {
+    let mut instances = scan_all(&store.get::<JavaSettings>().scan_options()).await;
+    instances.sort_by(|a, b| b.cmp(a));
+    Ok(instances)
+}
+
+/// Accepts a java home or a java executable, and remembers its java home in the settings.
+#[command]
+pub async fn add_java_runtime(
+    store: State<'_, Arc<SettingsStore>>,
+    path: String,
+) -> CommandResult<JavaInstance> {
+    let source: Box<dyn JavaScanSource> = Box::new(UserPathSource {
+        paths: vec![PathBuf::from(&path)],
+    });
+    let instance = scan_sources(&[source])
+        .await
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("No usable java runtime found at {path}"))?;
+    let java_home = instance.java_home.to_string_lossy().to_string();
+    store
+        .update_atomic::<JavaSettings, _>(|settings| {
+            if settings.extra_paths.contains(&java_home) {
+                return Ok(false);
+            }
+            settings.extra_paths.push(java_home);
+            Ok(true)
+        })
+        .await?;
+    Ok(instance)
+}
+
+/// Forgets a user added java, or deletes one installed by the launcher.
+#[command]
+pub async fn remove_java_runtime(
+    store: State<'_, Arc<SettingsStore>>,
+    java_home: String,
+) -> CommandResult<()> {
+    let java_home = PathBuf::from(java_home);
+    let canonical_home = fs::canonicalize(&java_home)
+        .await
+        .unwrap_or_else(|_| java_home.clone());
+    let mut forgotten = false;
+    store
+        .update_atomic::<JavaSettings, _>(|settings| {
+            let count = settings.extra_paths.len();
+            // only the entry of this java, a parent directory must not forget its neighbours
+            settings.extra_paths.retain(|path| {
+                let path = Path::new(path);
+                let same = path == java_home
+                    || std::fs::canonicalize(path).is_ok_and(|path| path == canonical_home);
+                !same
+            });
+            forgotten = settings.extra_paths.len() != count;
+            Ok(forgotten)
+        })
+        .await?;
+    if forgotten {
+        return Ok(());
+    }
+
+    let runtime_dir = app_dir()?.join(RUNTIME_DIR_NAME);
+    let Some(install_dir) = managed_install_dir(&runtime_dir, &java_home).await else {
+        return Err(anyhow!("{} was not added through the launcher", java_home.display()).into());
+    };
+    fs::remove_dir_all(&install_dir)
+        .await
+        .map_err(anyhow::Error::from)?;
+    Ok(())
+}
+
+// only a real directory directly inside the runtime dir may be deleted, the check runs on
+// canonical paths so `..` and symlinks cannot point it elsewhere
+async fn managed_install_dir(runtime_dir: &Path, java_home: &Path) -> Option<PathBuf> {
+    if fs::symlink_metadata(java_home).await.ok()?.is_symlink() {
+        return None;
+    }
+    let runtime_dir = fs::canonicalize(runtime_dir).await.ok()?;
+    let java_home = fs::canonicalize(java_home).await.ok()?;
+    // macOS builds keep the java home inside a bundle
+    let install_dir = if java_home.ends_with("Contents/Home") {
+        java_home.parent()?.parent()?.to_path_buf()
+    } else {
+        java_home
+    };
+    let is_dir = fs::symlink_metadata(&install_dir).await.ok()?.is_dir();
+    (is_dir && install_dir.parent() == Some(runtime_dir.as_path())).then_some(install_dir)
+}
+
+#[command]
+pub async fn select_java_runtime(
+    store: State<'_, Arc<SettingsStore>>,
+    major_version: u32,
+    component: String,
+) -> CommandResult<JavaSelection> {
+    let java_version = JavaVersion {
+        component,
+        major_version,
+    };
+    let rule_context = RuleContext {
+        os_info: os_info::get(),
+        user_features: Default::default(),
+    };
+    let requirement = JavaRequirement::new(&java_version, &rule_context);
+    let settings = store.get::<JavaSettings>();
+    let instances = scan_all(&settings.scan_options()).await;
+    Ok(select_java(instances, &requirement, &settings.vendor_preference).into())
+}
+
+#[command]
+pub async fn install_java_runtime(
+    catalog: State<'_, Arc<VersionCatalogClient>>,
+    scheduler: State<'_, Scheduler>,
+    component: String,
+) -> CommandResult<JavaInstance> {
+    let rule_context = RuleContext {
+        os_info: os_info::get(),
+        user_features: Default::default(),
+    };
+    let runtime_dir = AbsPathBuf::new(&app_dir()?.join(RUNTIME_DIR_NAME).join(&component))?;
+    let task = install_mojang_runtime_task(
+        catalog.inner().clone(),
+        component,
+        runtime_platform(&rule_context).to_string(),
+        runtime_dir,
+    );
+    Ok(scheduler.run(task).await?)
+}
+
+#[command]
+pub async fn install_vendor_jdk(
+    store: State<'_, Arc<SettingsStore>>,
+    scheduler: State<'_, Scheduler>,
+    vendor: JavaVendor,
+    major_version: u32,
+    arch: JavaArch,
+) -> CommandResult<JavaInstance> {
+    let query = VendorJdkQuery {
+        vendor,
+        major_version,
+        arch,
+        os_type: os_info::get().os_type(),
+    };
+    let endpoints = store.get::<JavaSettings>().vendor_endpoints();
+    let runtime_dir = AbsPathBuf::new(&app_dir()?.join(RUNTIME_DIR_NAME))?;
+    let task = install_vendor_jdk_task(Client::new(), endpoints, query, runtime_dir);
+    Ok(scheduler.run(task).await?)
+}
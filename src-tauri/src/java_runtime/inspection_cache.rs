@@ -0,0 +1,184 @@
+use crate::java_runtime::inspector::inspect_java_executable;
+use crate::java_runtime::models::JavaInstance;
+use log::warn;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tokio::fs;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct FileStamp {
+    modified_millis: u64,
+    size: u64,
+    // the inspection prefers the release file next to the bin directory
+    release_modified_millis: Option<u64>,
+}
+
+// failed inspections are not cached, a timeout must not hide a working java
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct CachedInspection {
+    #[serde(flatten)]
+    stamp: FileStamp,
+    instance: JavaInstance,
+}
+
+/// Inspection results keyed by executable path, valid while the executable and its
+/// release file are unchanged.
+#[derive(Debug, Default)]
+pub struct JavaInspectionCache {
+    path: Option<PathBuf>,
+    entries: Mutex<HashMap<PathBuf, CachedInspection>>,
+}
+
+impl JavaInspectionCache {
+    pub async fn load(path: PathBuf) -> Self {
+        let entries = match fs::read(&path).await {
+            Ok(content) => serde_json::from_slice(&content).unwrap_or_else(|e| {
+                warn!("Ignoring invalid java inspection cache {path:?}: {e}");
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        Self {
+            path: Some(path),
+            entries: Mutex::new(entries),
+        }
+    }
+
+    pub async fn inspect(&self, executable: PathBuf) -> Option<JavaInstance> {
+        let stamp = file_stamp(&executable).await?;
+        if let Some(cached) = self.entries.lock().get(&executable)
+            && cached.stamp == stamp
+        {
+            return Some(cached.instance.clone());
+        }
+
+        let Some(instance) = inspect_java_executable(executable.clone()).await else {
+            self.entries.lock().remove(&executable);
+            return None;
+        };
+        self.entries.lock().insert(
+            executable,
+            CachedInspection {
+                stamp,
+                instance: instance.clone(),
+            },
+        );
+        Some(instance)
+    }
+
+    /// Writes the cache back, dropping executables that no longer exist.
+    pub async fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let content = {
+            let mut entries = self.entries.lock();
+            entries.retain(|executable, _| executable.is_file());
+            serde_json::to_vec(&*entries)
+        };
+        // written next to the cache and renamed, so a crash never leaves a truncated file
+        let temp_path = path.with_file_name(format!(".{}.tmp", Uuid::new_v4()));
+        let result = async {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::write(&temp_path, content?).await?;
+            fs::rename(&temp_path, path).await?;
+            anyhow::Ok(())
+        }
+        .await;
+        if let Err(e) = result {
+            let _ = fs::remove_file(&temp_path).await;
+            warn!("Failed to save java inspection cache: {e:?}");
+        }
+    }
+}
+
+// modification time in millis and size
+async fn metadata_stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).await.ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((modified.as_millis() as u64, metadata.len()))
+}
+
+async fn file_stamp(executable: &Path) -> Option<FileStamp> {
+    let (modified_millis, size) = metadata_stamp(executable).await?;
+    let release = executable.parent()?.parent()?.join("release");
+    let release_modified_millis = metadata_stamp(&release).await.map(|(modified, _)| modified);
+    Some(FileStamp {
+        modified_millis,
+        size,
+        release_modified_millis,
+    })
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn fake_java_home(home: &Path, java_version: &str) -> PathBuf {
+        let java = home.join("bin").join("java");
+        std::fs::create_dir_all(java.parent().unwrap()).unwrap();
+        std::fs::write(&java, b"").unwrap();
+        let release = format!("JAVA_VERSION=\"{java_version}\"\nOS_ARCH=\"x86_64\"\n");
+        std::fs::write(home.join("release"), release).unwrap();
+        java
+    }
+
+    fn set_modified(path: &Path, seconds: u64) {
+        let time = UNIX_EPOCH + std::time::Duration::from_secs(seconds);
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(time).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reuses_entries_until_the_executable_or_release_changes() {
+        let root = TempDir::new().unwrap();
+        let cache_path = root.path().join("cache.json");
+        let java = fake_java_home(&root.path().join("jdk"), "17.0.8");
+        let release_path = root.path().join("jdk/release");
+        set_modified(&release_path, 1_000);
+        let cache = JavaInspectionCache::load(cache_path.clone()).await;
+        assert_eq!(cache.inspect(java.clone()).await.unwrap().major_version, 17);
+        cache.save().await;
+        let saved: Vec<_> = std::fs::read_dir(root.path()).unwrap().collect();
+        assert_eq!(saved.len(), 2, "Only the cache and the jdk should be left");
+
+        let reloaded = JavaInspectionCache::load(cache_path.clone()).await;
+        reloaded
+            .entries
+            .lock()
+            .get_mut(&java)
+            .unwrap()
+            .instance
+            .vendor_name = "From Cache".to_string();
+        assert_eq!(
+            reloaded.inspect(java.clone()).await.unwrap().vendor_name,
+            "From Cache"
+        );
+
+        let release = "JAVA_VERSION=\"21.0.1\"\nOS_ARCH=\"x86_64\"\n";
+        std::fs::write(&release_path, release).unwrap();
+        set_modified(&release_path, 2_000);
+        assert_eq!(reloaded.inspect(java).await.unwrap().major_version, 21);
+    }
+
+    #[tokio::test]
+    async fn test_failed_inspections_are_not_cached() {
+        let root = TempDir::new().unwrap();
+        let java = fake_java_home(&root.path().join("jdk"), "17.0.8");
+        std::fs::write(root.path().join("jdk/release"), "garbage").unwrap();
+        let cache = JavaInspectionCache::load(root.path().join("cache.json")).await;
+
+        assert!(cache.inspect(java.clone()).await.is_none());
+        assert!(cache.entries.lock().is_empty());
+    }
+}
@@ -1,18 +1,27 @@
-use crate::java_runtime::models::{JavaArch, JavaInstance};
+use crate::java_runtime::models::{JavaArch, JavaInstance, JavaSource, JavaVersionParts};
 use crate::java_runtime::vendors::VENDOR_KEYWORDS_MAP;
 use crate::utils::executor::Executable;
 use anyhow::{Context, Result, anyhow};
 use log::warn;
 use regex::Regex;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 use std::time::Duration;
 use tap::Pipe;
+use tokio::fs;
 use tokio::time::timeout;
 
 const JAVA_VERSION_DETECT_TIMEOUT: Duration = Duration::from_secs(3);
 
 pub async fn inspect_java_executable(path: PathBuf) -> Option<JavaInstance> {
+    if let Some(content) = read_release_file(&path).await {
+        match parse_release_file(path.clone(), &content) {
+            Ok(instance) => return Some(instance),
+            Err(err) => warn!("Invalid release file for {path:?}, running it instead: {err:?}"),
+        }
+    }
+
     let output = match execute_java_version_command(path.clone()).await {
         Ok(out) => out,
         Err(_) => return None,
@@ -27,6 +36,50 @@ pub async fn inspect_java_executable(path: PathBuf) -> Option<JavaInstance> {
     }
 }
 
+// `<java home>/release`, next to the bin directory
+async fn read_release_file(path: &Path) -> Option<String> {
+    let java_home = path.parent()?.parent()?;
+    fs::read_to_string(java_home.join("release")).await.ok()
+}
+
+fn parse_release_file(path: PathBuf, content: &str) -> Result<JavaInstance> {
+    let properties: HashMap<&str, &str> = content
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim().trim_matches('"')))
+        .collect();
+
+    // JAVA_RUNTIME_VERSION looks like 17.0.8+7-LTS
+    let version = properties
+        .get("JAVA_VERSION")
+        .or_else(|| properties.get("JAVA_RUNTIME_VERSION"))
+        .map(|version| version.split(['+', '-']).next().unwrap_or(version))
+        .filter(|version| !version.is_empty())
+        .context("No JAVA_VERSION in release file")?
+        .to_string();
+    let arch = parse_os_arch(
+        properties
+            .get("OS_ARCH")
+            .context("No OS_ARCH in release file")?,
+    );
+    let vendor_name = properties
+        .get("IMPLEMENTOR")
+        .map(|implementor| detect_vendor(implementor.to_string()))
+        .unwrap_or_else(|| "Unknown".to_string());
+    let runtime_version = properties.get("JAVA_RUNTIME_VERSION").copied();
+
+    build_instance(path, version, runtime_version, arch, vendor_name)
+}
+
+fn parse_os_arch(arch: &str) -> JavaArch {
+    match arch.to_lowercase().as_str() {
+        "aarch64" | "arm64" => JavaArch::Arm64,
+        "x86_64" | "amd64" | "x64" => JavaArch::X64,
+        "x86" | "i386" | "i586" | "i686" => JavaArch::X86,
+        other => JavaArch::Unknown(other.to_string()),
+    }
+}
+
 async fn execute_java_version_command(path: PathBuf) -> Result<String> {
     let exec = Executable {
         program: path.to_string_lossy().to_string(),
@@ -46,19 +99,59 @@ async fn execute_java_version_command(path: PathBuf) -> Result<String> {
 
 fn parse_java_info(path: PathBuf, output: String) -> Result<JavaInstance> {
     let version_str = extract_raw_version(output.clone())?;
-    let major_version = parse_major_version(version_str.clone())?;
     let arch = determine_architecture(output.clone());
     let vendor_name = extract_and_detect_vendor(output.clone());
+    let runtime_version = extract_runtime_version(&output);
+
+    build_instance(path, version_str, runtime_version, arch, vendor_name)
+}
+
+// the executable is expected at <java home>/bin/java
+fn build_instance(
+    executable_path: PathBuf,
+    version: String,
+    runtime_version: Option<&str>,
+    arch: JavaArch,
+    vendor_name: String,
+) -> Result<JavaInstance> {
+    let major_version = parse_major_version(version.clone())?;
+    let version_parts = runtime_version
+        .and_then(JavaVersionParts::parse)
+        .or_else(|| JavaVersionParts::parse(&version))
+        .unwrap_or_default();
+    let java_home = executable_path
+        .parent()
+        .and_then(Path::parent)
+        .unwrap_or(Path::new(""))
+        .to_path_buf();
+    let bin = java_home.join("bin");
 
     Ok(JavaInstance {
-        path,
-        version: version_str,
+        is_jdk: bin.join("javac").is_file() || bin.join("javac.exe").is_file(),
+        has_javaw: bin.join("javaw.exe").is_file(),
+        java_home,
+        executable_path,
+        version,
+        version_parts,
         major_version,
+        is_64bit: arch.is_64bit(),
         arch,
         vendor_name,
+        source: JavaSource::System,
     })
 }
 
+fn extract_runtime_version(output: &str) -> Option<&str> {
+    static RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"\(build ([^)\s,]+)")
+            .expect("Internal Error: Failed to compile java_runtime_version regex")
+    });
+
+    RE.captures(output)
+        .and_then(|c| c.get(1))
+        .map(|m| m.as_str())
+}
+
 fn extract_raw_version(output: String) -> Result<String> {
     static RE: LazyLock<Regex> = LazyLock::new(|| {
         Regex::new(r#"version "([^"]+)""#)
@@ -109,7 +202,7 @@ fn extract_and_detect_vendor(output: String) -> String {
     detect_vendor(raw_vendor_line.to_string())
 }
 
-fn detect_vendor(raw: String) -> String {
+pub(crate) fn detect_vendor(raw: String) -> String {
     let raw_lower = raw.to_lowercase();
 
     for (k, v) in VENDOR_KEYWORDS_MAP {
@@ -153,6 +246,15 @@ Java HotSpot(TM) Client VM (build 24.80-b15, mixed mode)"#;
 
         assert_eq!(instance.major_version, 17);
         assert_eq!(instance.version, "17.0.1");
+        assert_eq!(
+            instance.version_parts,
+            JavaVersionParts {
+                major: 17,
+                minor: 0,
+                security: 1,
+                build: Some(12),
+            }
+        );
     }
 
     #[test]
@@ -161,6 +263,8 @@ Java HotSpot(TM) Client VM (build 24.80-b15, mixed mode)"#;
 
         assert_eq!(instance.major_version, 8);
         assert_eq!(instance.version, "1.8.0_301");
+        assert_eq!(instance.version_parts.security, 301);
+        assert_eq!(instance.version_parts.build, Some(9));
     }
 
     #[test]
@@ -212,6 +316,39 @@ Java HotSpot(TM) Client VM (build 24.80-b15, mixed mode)"#;
         assert!(instance.is_err(), "Non-numeric version should return error");
     }
 
+    #[test]
+    fn should_read_version_arch_and_vendor_from_release_file() {
+        let release = r#"IMPLEMENTOR="Eclipse Adoptium"
+IMPLEMENTOR_VERSION="Temurin-17.0.8+7"
+JAVA_RUNTIME_VERSION="17.0.8+7"
+JAVA_VERSION="17.0.8"
+OS_ARCH="aarch64"
+OS_NAME="Linux""#;
+
+        let instance = parse_release_file(PathBuf::from("/mock/bin/java"), release).unwrap();
+
+        assert_eq!(instance.java_home, PathBuf::from("/mock"));
+        assert_eq!(instance.version, "17.0.8");
+        assert_eq!(instance.version_parts.build, Some(7));
+        assert!(instance.is_64bit);
+        assert_eq!(instance.major_version, 17);
+        assert_eq!(instance.arch, JavaArch::Arm64);
+        assert_eq!(instance.vendor_name, "Eclipse Temurin");
+    }
+
+    #[test]
+    fn should_fall_back_to_runtime_version_in_release_file() {
+        let release = "JAVA_RUNTIME_VERSION=\"1.8.0_382-b05\"\nOS_ARCH=\"i586\"\n";
+
+        let instance = parse_release_file(PathBuf::from("/mock/bin/java"), release).unwrap();
+
+        assert_eq!(instance.version, "1.8.0_382");
+        assert_eq!(instance.major_version, 8);
+        assert_eq!(instance.arch, JavaArch::X86);
+        assert!(!instance.is_64bit);
+        assert_eq!(instance.vendor_name, "Unknown");
+    }
+
     #[test]
     fn should_fail_when_output_contains_no_version_string() {
         let invalid_output = "Command not found or invalid output";
@@ -1,4 +1,9 @@
+pub mod commands;
+pub mod inspection_cache;
 pub mod inspector;
 pub mod models;
+pub mod mojang;
 pub mod scanner;
+pub mod selector;
+pub mod vendor_download;
 mod vendors;
@@ -1,19 +1,32 @@
 use crate::game_launcher::models::LaunchError::InvalidJavaRuntime;
+use crate::game_resolver::models::DownloadFile;
 use anyhow::Result;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use specta::Type;
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
+use std::sync::LazyLock;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
 pub struct JavaInstance {
-    pub path: PathBuf,
+    pub java_home: PathBuf,
+    // bin/java, or bin/java.exe on windows
+    pub executable_path: PathBuf,
     pub version: String,
+    pub version_parts: JavaVersionParts,
     pub major_version: u32,
     pub arch: JavaArch,
+    pub is_64bit: bool,
     pub vendor_name: String,
+    pub is_jdk: bool,
+    pub has_javaw: bool,
+    pub source: JavaSource,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Type)]
 pub enum JavaArch {
     X86,
     X64,
@@ -21,14 +34,58 @@ pub enum JavaArch {
     Unknown(String),
 }
 
-impl JavaInstance {
-    pub fn get_java_executable_path(&self) -> PathBuf {
-        self.path.clone().join("bin").join("java")
+impl JavaArch {
+    pub fn is_64bit(&self) -> bool {
+        match self {
+            JavaArch::X86 => false,
+            JavaArch::X64 | JavaArch::Arm64 => true,
+            JavaArch::Unknown(arch) => arch.contains("64"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum JavaSource {
+    System,
+    // installed by the launcher into its runtime dir
+    Managed,
+    // added by hand in the settings
+    User,
+}
+
+/// `1.8.0_382-b05` is major 8, update 382, build 5; `17.0.8+7` is 17.0.8 build 7.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct JavaVersionParts {
+    pub major: u32,
+    pub minor: u32,
+    pub security: u32,
+    pub build: Option<u32>,
+}
+
+impl JavaVersionParts {
+    pub fn parse(version: &str) -> Option<Self> {
+        static RE: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"^(?:1\.)?(\d+)(?:\.(\d+))?(?:\.(\d+))?(?:_(\d+))?(?:[+-]b?(\d+))?")
+                .expect("Internal Error: Failed to compile java_version_parts regex")
+        });
+
+        let captures = RE.captures(version.trim())?;
+        let number = |i: usize| captures.get(i).and_then(|m| m.as_str().parse().ok());
+        Some(JavaVersionParts {
+            major: number(1)?,
+            minor: number(2).unwrap_or(0),
+            security: number(4).or_else(|| number(3)).unwrap_or(0),
+            build: number(5),
+        })
     }
+}
 
+impl JavaInstance {
     pub fn get_java_executable_path_str(&self) -> Result<String> {
         let str = self
-            .get_java_executable_path()
+            .executable_path
             .to_str()
             .ok_or(InvalidJavaRuntime)?
             .to_string();
@@ -47,3 +104,43 @@ impl PartialOrd for JavaInstance {
         Some(self.cmp(other))
     }
 }
+
+// platform -> component -> runtimes, as served by mojang's java-runtime all.json
+pub type JavaRuntimeIndex = HashMap<String, HashMap<String, Vec<JavaRuntimeEntry>>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct JavaRuntimeEntry {
+    pub manifest: DownloadFile,
+    pub version: JavaRuntimeVersion,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct JavaRuntimeVersion {
+    pub name: String,
+    pub released: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct JavaRuntimeManifest {
+    pub files: BTreeMap<String, JavaRuntimeFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum JavaRuntimeFile {
+    File {
+        #[serde(default)]
+        executable: bool,
+        downloads: JavaRuntimeFileDownloads,
+    },
+    Directory,
+    Link {
+        target: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct JavaRuntimeFileDownloads {
+    pub raw: DownloadFile,
+    pub lzma: Option<DownloadFile>,
+}
@@ -0,0 +1,440 @@
+use crate::constants::network::JAVA_RUNTIME_INDEX_PATH;
+use crate::game_installer::catalog::VersionCatalogClient;
+use crate::game_installer::download::{
+    DownloadError, download_file, fetch_json, file_sha1, is_file_intact,
+};
+use crate::game_launcher::models::RuleContext;
+use crate::game_resolver::models::DownloadFile;
+use crate::java_runtime::inspector::inspect_java_executable;
+use crate::java_runtime::models::{
+    JavaInstance, JavaRuntimeEntry, JavaRuntimeFile, JavaRuntimeIndex, JavaRuntimeManifest,
+    JavaSource,
+};
+use crate::java_runtime::scanner::java_in_home;
+use crate::scheduler::{Context, Task, parallel, task, task_with_ctx};
+use crate::utils::abs_path_buf::AbsPathBuf;
+use crate::utils::os_info::{is_macos, is_windows};
+use anyhow::{Context as _, Result, anyhow};
+use log::info;
+use reqwest::Client;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::fs;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RuntimeFileDownload {
+    pub name: String,
+    pub path: AbsPathBuf,
+    pub raw: DownloadFile,
+    pub lzma: Option<DownloadFile>,
+    pub executable: bool,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RuntimeLink {
+    pub path: AbsPathBuf,
+    pub target: String,
+}
+
+/// The platform key of mojang's runtime index for the host, e.g. `linux` or `mac-os-arm64`.
+pub fn runtime_platform(rule_context: &RuleContext) -> &'static str {
+    let arch = rule_context
+        .os_info
+        .architecture()
+        .unwrap_or_default()
+        .to_lowercase();
+    let is_arm64 = arch.contains("aarch64") || arch.contains("arm64");
+    let is_x86 = matches!(arch.as_str(), "x86" | "i386" | "i586" | "i686");
+    if is_windows(&rule_context.os_info) {
+        match (is_arm64, is_x86) {
+            (true, _) => "windows-arm64",
+            (_, true) => "windows-x86",
+            _ => "windows-x64",
+        }
+    } else if is_macos(&rule_context.os_info) {
+        if is_arm64 { "mac-os-arm64" } else { "mac-os" }
+    } else if is_x86 {
+        "linux-i386"
+    } else {
+        "linux"
+    }
+}
+
+pub fn get_runtime_index_url(catalog: &VersionCatalogClient) -> String {
+    let base_url = catalog.base_url();
+    format!(
+        "{}/{JAVA_RUNTIME_INDEX_PATH}",
+        base_url.trim_end_matches('/')
+    )
+}
+
+pub fn find_runtime<'a>(
+    index: &'a JavaRuntimeIndex,
+    platform: &str,
+    component: &str,
+) -> Result<&'a JavaRuntimeEntry> {
+    index
+        .get(platform)
+        .and_then(|components| components.get(component))
+        .and_then(|runtimes| runtimes.first())
+        .ok_or_else(|| anyhow!("Java runtime '{component}' is not available for {platform}"))
+}
+
+pub fn collect_runtime_files(
+    manifest: &JavaRuntimeManifest,
+    runtime_dir: &AbsPathBuf,
+) -> (Vec<RuntimeFileDownload>, Vec<RuntimeLink>) {
+    let mut downloads = vec![];
+    let mut links = vec![];
+    for (name, file) in &manifest.files {
+        let path = runtime_dir.join(name);
+        match file {
+            JavaRuntimeFile::File {
+                executable,
+                downloads: file_downloads,
+            } => downloads.push(RuntimeFileDownload {
+                name: name.clone(),
+                path,
+                raw: file_downloads.raw.clone(),
+                lzma: file_downloads.lzma.clone(),
+                executable: *executable,
+            }),
+            JavaRuntimeFile::Link { target } => links.push(RuntimeLink {
+                path,
+                target: target.clone(),
+            }),
+            // parents are created along with the files
+            JavaRuntimeFile::Directory => {}
+        }
+    }
+    (downloads, links)
+}
+
+/// Installs a mojang java runtime `component` into `runtime_dir` and inspects the result.
+pub fn install_mojang_runtime_task(
+    catalog: Arc<VersionCatalogClient>,
+    component: String,
+    platform: String,
+    runtime_dir: AbsPathBuf,
+) -> impl Task<Input = (), Output = JavaInstance> {
+    task_with_ctx(
+        format!("Install java runtime {component}"),
+        move |_: (), ctx| {
+            install_mojang_runtime(
+                catalog.clone(),
+                component.clone(),
+                platform.clone(),
+                runtime_dir.clone(),
+                ctx,
+            )
+        },
+    )
+}
+
+async fn install_mojang_runtime(
+    catalog: Arc<VersionCatalogClient>,
+    component: String,
+    platform: String,
+    runtime_dir: AbsPathBuf,
+    ctx: Context,
+) -> Result<JavaInstance> {
+    let client = Client::new();
+    let index: JavaRuntimeIndex =
+        fetch_json(&client, &get_runtime_index_url(&catalog), None).await?;
+    let runtime = find_runtime(&index, &platform, &component)?;
+    // every file hash below comes from this manifest
+    let manifest_url = catalog.resolve_url(&runtime.manifest.url);
+    let manifest: JavaRuntimeManifest =
+        fetch_json(&client, &manifest_url, Some(&runtime.manifest.sha1)).await?;
+    info!(
+        "Installing java runtime {component} {} into {}",
+        runtime.version.name,
+        runtime_dir.display()
+    );
+
+    let (downloads, links) = collect_runtime_files(&manifest, &runtime_dir);
+    let downloads_task = download_runtime_files_task(client, catalog, downloads);
+    downloads_task.run((), ctx).await?;
+    for link in &links {
+        create_link(link).await?;
+    }
+
+    let java = java_in_home(&runtime_dir);
+    let mut instance = inspect_java_executable(java)
+        .await
+        .ok_or_else(|| anyhow!("Installed java runtime {component} cannot be started"))?;
+    instance.source = JavaSource::Managed;
+    Ok(instance)
+}
+
+pub fn download_runtime_files_task(
+    client: Client,
+    catalog: Arc<VersionCatalogClient>,
+    downloads: Vec<RuntimeFileDownload>,
+) -> impl Task<Input = (), Output = Vec<()>> {
+    let tasks = downloads.into_iter().map(move |download| {
+        let client = client.clone();
+        let catalog = catalog.clone();
+        let weight = (download.raw.size / 1024).max(1);
+        task(download.name.clone(), move |_: ()| {
+            download_runtime_file(client.clone(), catalog.clone(), download.clone())
+        })
+        .with_weight(weight)
+    });
+    parallel("Download java runtime files")
+        .extend(tasks)
+        .build()
+}
+
+async fn download_runtime_file(
+    client: Client,
+    catalog: Arc<VersionCatalogClient>,
+    download: RuntimeFileDownload,
+) -> Result<()> {
+    let raw = &download.raw;
+    if !is_file_intact(&download.path, Some(&raw.sha1), Some(raw.size)).await {
+        match &download.lzma {
+            Some(lzma) => download_lzma_file(&client, &catalog, lzma, &download).await?,
+            None => {
+                let url = catalog.resolve_url(&raw.url);
+                download_file(
+                    &client,
+                    &url,
+                    &download.path,
+                    Some(&raw.sha1),
+                    Some(raw.size),
+                )
+                .await?
+            }
+        }
+    }
+    if download.executable {
+        set_executable(&download.path).await?;
+    }
+    Ok(())
+}
+
+async fn download_lzma_file(
+    client: &Client,
+    catalog: &VersionCatalogClient,
+    lzma: &DownloadFile,
+    download: &RuntimeFileDownload,
+) -> Result<()> {
+    let mut lzma_path = download.path.as_os_str().to_owned();
+    lzma_path.push(".lzma");
+    let lzma_path = AbsPathBuf::new(&lzma_path)?;
+    let url = catalog.resolve_url(&lzma.url);
+    download_file(client, &url, &lzma_path, Some(&lzma.sha1), Some(lzma.size)).await?;
+
+    let (source, dest) = (lzma_path.to_path_buf(), download.path.to_path_buf());
+    let result = tokio::task::spawn_blocking(move || decompress_lzma(&source, &dest)).await?;
+    let _ = fs::remove_file(&lzma_path).await;
+    result.with_context(|| format!("Failed to decompress {}", download.name))?;
+
+    let actual = file_sha1(&download.path).await?;
+    if !actual.eq_ignore_ascii_case(&download.raw.sha1) {
+        let _ = fs::remove_file(&download.path).await;
+        return Err(DownloadError::ChecksumMismatch {
+            url,
+            expected: download.raw.sha1.clone(),
+            actual,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+fn decompress_lzma(source: &Path, dest: &Path) -> Result<()> {
+    let mut input = BufReader::new(File::open(source)?);
+    let mut output = File::create(dest)?;
+    lzma_rs::lzma_decompress(&mut input, &mut output)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn set_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+        .await
+        .with_context(|| format!("Failed to make {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+async fn set_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn create_link(link: &RuntimeLink) -> Result<()> {
+    if let Some(parent) = link.path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    if fs::symlink_metadata(&link.path).await.is_ok() {
+        fs::remove_file(&link.path).await?;
+    }
+    fs::symlink(&link.target, &link.path)
+        .await
+        .with_context(|| format!("Failed to link {} to {}", link.path.display(), link.target))
+}
+
+#[cfg(not(unix))]
+async fn create_link(link: &RuntimeLink) -> Result<()> {
+    anyhow::bail!("Links are not supported here: {}", link.path.display())
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::scheduler::Scheduler;
+    use crate::utils::os_info::mock_info;
+    use crate::utils::test_server::TestServer;
+    use os_info::Type;
+    use serde_json::json;
+    use sha1::{Digest, Sha1};
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn sha1_hex(data: &[u8]) -> String {
+        hex::encode(Sha1::digest(data))
+    }
+
+    fn lzma(data: &[u8]) -> Vec<u8> {
+        let mut output = vec![];
+        lzma_rs::lzma_compress(&mut &data[..], &mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn test_runtime_platform() {
+        let platform = |os_type, arch| {
+            runtime_platform(&RuleContext {
+                os_info: mock_info(os_type, "1.0", arch),
+                user_features: HashMap::new(),
+            })
+        };
+
+        assert_eq!(platform(Type::Linux, "x86_64"), "linux");
+        assert_eq!(platform(Type::Linux, "i686"), "linux-i386");
+        assert_eq!(platform(Type::Macos, "arm64"), "mac-os-arm64");
+        assert_eq!(platform(Type::Windows, "x86_64"), "windows-x64");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_install_runtime_through_mirror() {
+        let java_script = b"#!/bin/sh\necho 'openjdk version \"17.0.8\" 2023-07-18' >&2\n";
+        let java_lzma = lzma(java_script);
+        let license = b"GPLv2 with classpath exception";
+        // urls point to mojang, the catalog routes them to the test server
+        let object_url = |name: &str| format!("https://piston-data.mojang.com/v1/objects/{name}");
+        let manifest = json!({
+            "files": {
+                "bin": { "type": "directory" },
+                "bin/java": {
+                    "type": "file",
+                    "executable": true,
+                    "downloads": {
+                        "raw": { "sha1": sha1_hex(java_script), "size": java_script.len(), "url": object_url("java") },
+                        "lzma": { "sha1": sha1_hex(&java_lzma), "size": java_lzma.len(), "url": object_url("java.lzma") }
+                    }
+                },
+                "legal/LICENSE": {
+                    "type": "file",
+                    "downloads": {
+                        "raw": { "sha1": sha1_hex(license), "size": license.len(), "url": object_url("license") }
+                    }
+                },
+                "legal/COPYRIGHT": { "type": "link", "target": "LICENSE" }
+            }
+        })
+        .to_string();
+        let index = json!({
+            "linux": {
+                "java-runtime-gamma": [{
+                    "manifest": { "sha1": sha1_hex(manifest.as_bytes()), "size": manifest.len(), "url": object_url("manifest.json") },
+                    "version": { "name": "17.0.8", "released": "2023-07-18T00:00:00+00:00" }
+                }]
+            }
+        })
+        .to_string();
+        let server = TestServer::start(HashMap::from([
+            (format!("/{JAVA_RUNTIME_INDEX_PATH}"), index.into_bytes()),
+            (
+                "/piston-data/v1/objects/manifest.json".to_string(),
+                manifest.into_bytes(),
+            ),
+            ("/piston-data/v1/objects/java.lzma".to_string(), java_lzma),
+            (
+                "/piston-data/v1/objects/license".to_string(),
+                license.to_vec(),
+            ),
+        ]))
+        .await;
+        let catalog = Arc::new(VersionCatalogClient::new(Client::new(), &server.base_url));
+        let root = TempDir::new().unwrap();
+        let runtime_dir = AbsPathBuf::new(&root.path().join("java-runtime-gamma")).unwrap();
+        let task = install_mojang_runtime_task(
+            catalog,
+            "java-runtime-gamma".to_string(),
+            "linux".to_string(),
+            runtime_dir.clone(),
+        );
+
+        let java = Scheduler::new(8).run(task).await.unwrap();
+
+        assert_eq!(java.major_version, 17);
+        assert_eq!(
+            std::fs::read(runtime_dir.join("bin/java")).unwrap(),
+            java_script
+        );
+        assert!(!runtime_dir.join("bin/java.lzma").exists());
+        assert_eq!(
+            std::fs::read_link(runtime_dir.join("legal/COPYRIGHT")).unwrap(),
+            Path::new("LICENSE")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_a_runtime_manifest_with_wrong_sha1() {
+        let manifest = json!({ "files": {} }).to_string();
+        let index = json!({
+            "linux": {
+                "java-runtime-gamma": [{
+                    "manifest": {
+                        "sha1": sha1_hex(b"another manifest"),
+                        "size": manifest.len(),
+                        "url": "https://piston-meta.mojang.com/v1/packages/manifest.json"
+                    },
+                    "version": { "name": "17.0.8", "released": "2023-07-18T00:00:00+00:00" }
+                }]
+            }
+        })
+        .to_string();
+        let server = TestServer::start(HashMap::from([
+            (format!("/{JAVA_RUNTIME_INDEX_PATH}"), index.into_bytes()),
+            (
+                "/v1/packages/manifest.json".to_string(),
+                manifest.into_bytes(),
+            ),
+        ]))
+        .await;
+        let catalog = Arc::new(VersionCatalogClient::new(Client::new(), &server.base_url));
+        let root = TempDir::new().unwrap();
+        let task = install_mojang_runtime_task(
+            catalog,
+            "java-runtime-gamma".to_string(),
+            "linux".to_string(),
+            AbsPathBuf::new(root.path()).unwrap(),
+        );
+
+        let err = Scheduler::new(8).run(task).await.unwrap_err();
+
+        assert!(
+            format!("{err:?}").contains("Checksum mismatch"),
+            "Should reject the manifest, but got: {err:?}"
+        );
+    }
+}
@@ -1,98 +1,364 @@
-use crate::java_runtime::inspector::inspect_java_executable;
-use crate::java_runtime::models::JavaInstance;
+use crate::constants::file_system::{CACHE_DIR_NAME, RUNTIME_DIR_NAME};
+use crate::java_runtime::inspection_cache::JavaInspectionCache;
+use crate::java_runtime::models::{JavaInstance, JavaSource};
+use crate::utils::dirs::app_dir;
+use async_trait::async_trait;
 use futures::StreamExt;
 use futures::stream;
 use std::collections::HashSet;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tap::Pipe;
-use tokio::fs::read_dir;
+use tokio::fs::{canonicalize, read_dir};
 
 const INSPECT_JAVA_CONCURRENCY: usize = 16;
+const INSPECTION_CACHE_FILE_NAME: &str = "java_inspection.json";
 
-pub async fn scan_all() -> Vec<JavaInstance> {
-    candidate_paths()
-        .await
+/// A place java executables may be found in. Candidates do not need to exist.
+#[async_trait]
+pub trait JavaScanSource: Send + Sync {
+    async fn candidates(&self) -> Vec<PathBuf>;
+
+    fn source(&self) -> JavaSource {
+        JavaSource::System
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct JavaScanOptions {
+    // game directories whose `runtime` folder holds official launcher runtimes
+    pub minecraft_dirs: Vec<PathBuf>,
+    // java homes or executables added by the user
+    pub user_paths: Vec<PathBuf>,
+}
+
+pub async fn scan_all(options: &JavaScanOptions) -> Vec<JavaInstance> {
+    scan_sources(&default_sources(options)).await
+}
+
+pub async fn scan_sources(sources: &[Box<dyn JavaScanSource>]) -> Vec<JavaInstance> {
+    let cache = match app_dir() {
+        Ok(dir) => {
+            let path = dir.join(CACHE_DIR_NAME).join(INSPECTION_CACHE_FILE_NAME);
+            JavaInspectionCache::load(path).await
+        }
+        Err(_) => JavaInspectionCache::default(),
+    };
+    let candidates = candidate_paths(sources).await;
+    let instances = candidates
         .pipe(stream::iter)
-        .map(to_java_executable)
+        .map(|(path, source)| {
+            let cache = &cache;
+            async move {
+                let mut instance = cache.inspect(path).await?;
+                instance.source = source;
+                Some(instance)
+            }
+        })
         .buffer_unordered(INSPECT_JAVA_CONCURRENCY)
         .filter_map(|opt| async { opt })
         .collect()
-        .await
+        .await;
+    cache.save().await;
+    instances
 }
 
-async fn candidate_paths() -> Vec<PathBuf> {
-    [
-        scan_java_home().await,
-        scan_path_environment().await,
-        scan_current_directory().await,
-    ]
-    .into_iter()
-    .flatten()
-    .collect()
+pub fn default_sources(options: &JavaScanOptions) -> Vec<Box<dyn JavaScanSource>> {
+    let mut minecraft_dirs = options.minecraft_dirs.clone();
+    minecraft_dirs.extend(default_minecraft_dir());
+
+    // earlier sources win when the same java is found twice
+    let mut sources: Vec<Box<dyn JavaScanSource>> = vec![
+        Box::new(UserPathSource {
+            paths: options.user_paths.clone(),
+        }),
+        Box::new(ManagedRuntimeSource),
+        Box::new(JavaHomeSource),
+        Box::new(PathEnvironmentSource),
+        Box::new(CurrentDirectorySource),
+        Box::new(JavaHomesDirSource {
+            dirs: tool_managed_dirs(),
+        }),
+        Box::new(MinecraftRuntimeSource { minecraft_dirs }),
+    ];
+    if cfg!(target_os = "linux") {
+        sources.push(Box::new(JavaHomesDirSource {
+            dirs: vec![PathBuf::from("/usr/lib/jvm"), PathBuf::from("/opt")],
+        }));
+    }
+    sources
 }
 
-async fn to_java_executable(path: PathBuf) -> Option<JavaInstance> {
-    if is_executable(path.clone()).await {
-        inspect_java_executable(path).await
-    } else {
-        None
+// existing executables only, deduplicated by their real path so symlinks
+// such as `alternatives` entries or sdkman's `current` are inspected once
+async fn candidate_paths(sources: &[Box<dyn JavaScanSource>]) -> Vec<(PathBuf, JavaSource)> {
+    let mut seen = HashSet::new();
+    let mut paths = vec![];
+    for source in sources {
+        for candidate in source.candidates().await {
+            let Ok(real_path) = canonicalize(&candidate).await else {
+                continue;
+            };
+            if is_file(&real_path).await && seen.insert(real_path.clone()) {
+                paths.push((real_path, source.source()));
+            }
+        }
+    }
+    paths
+}
+
+pub struct JavaHomeSource;
+
+#[async_trait]
+impl JavaScanSource for JavaHomeSource {
+    async fn candidates(&self) -> Vec<PathBuf> {
+        match env::var_os("JAVA_HOME") {
+            Some(home_var) => vec![java_in_home(Path::new(&home_var))],
+            None => vec![],
+        }
     }
 }
 
-async fn scan_java_home() -> Vec<PathBuf> {
-    if let Some(home_var) = env::var_os("JAVA_HOME") {
-        let bin_path = PathBuf::from(home_var).join("bin").join(binary_name());
-        vec![bin_path]
-    } else {
-        vec![]
+pub struct PathEnvironmentSource;
+
+#[async_trait]
+impl JavaScanSource for PathEnvironmentSource {
+    async fn candidates(&self) -> Vec<PathBuf> {
+        let Some(path_var) = env::var_os("PATH") else {
+            return vec![];
+        };
+        env::split_paths(&path_var)
+            .map(|p| p.join(binary_name()))
+            .collect()
     }
 }
 
-async fn scan_path_environment() -> Vec<PathBuf> {
-    let Some(path_var) = env::var_os("PATH") else {
-        return vec![];
-    };
+pub struct CurrentDirectorySource;
 
-    stream::iter(env::split_paths(&path_var))
-        .map(|p| p.join(binary_name()))
-        .collect()
-        .await
+#[async_trait]
+impl JavaScanSource for CurrentDirectorySource {
+    async fn candidates(&self) -> Vec<PathBuf> {
+        let Ok(cwd) = env::current_dir() else {
+            return vec![];
+        };
+
+        let mut paths = vec![
+            cwd.join(binary_name()),
+            java_in_home(&cwd),
+            java_in_home(&cwd.join("java")),
+            java_in_home(&cwd.join("jre")),
+        ];
+        paths.extend(scan_java_homes_dir(&cwd.join(RUNTIME_DIR_NAME)).await);
+        paths
+    }
+}
+
+/// Runtimes installed by the launcher itself.
+pub struct ManagedRuntimeSource;
+
+#[async_trait]
+impl JavaScanSource for ManagedRuntimeSource {
+    async fn candidates(&self) -> Vec<PathBuf> {
+        match app_dir() {
+            Ok(dir) => scan_java_homes_dir(&dir.join(RUNTIME_DIR_NAME)).await,
+            Err(_) => vec![],
+        }
+    }
+
+    fn source(&self) -> JavaSource {
+        JavaSource::Managed
+    }
+}
+
+/// Directories whose children are java homes, e.g. `/usr/lib/jvm`.
+pub struct JavaHomesDirSource {
+    pub dirs: Vec<PathBuf>,
 }
 
-async fn scan_current_directory() -> Vec<PathBuf> {
-    let Ok(cwd) = env::current_dir() else {
+#[async_trait]
+impl JavaScanSource for JavaHomesDirSource {
+    async fn candidates(&self) -> Vec<PathBuf> {
+        let mut paths = vec![];
+        for dir in &self.dirs {
+            paths.extend(scan_java_homes_dir(dir).await);
+        }
+        paths
+    }
+}
+
+/// Runtimes the official launcher keeps in `runtime/<component>/<platform>/<component>`.
+pub struct MinecraftRuntimeSource {
+    pub minecraft_dirs: Vec<PathBuf>,
+}
+
+#[async_trait]
+impl JavaScanSource for MinecraftRuntimeSource {
+    async fn candidates(&self) -> Vec<PathBuf> {
+        let mut paths = vec![];
+        for minecraft_dir in &self.minecraft_dirs {
+            for component in sub_dirs(&minecraft_dir.join(RUNTIME_DIR_NAME)).await {
+                for platform in sub_dirs(&component).await {
+                    paths.extend(scan_java_homes_dir(&platform).await);
+                }
+            }
+        }
+        paths
+    }
+}
+
+pub struct UserPathSource {
+    pub paths: Vec<PathBuf>,
+}
+
+#[async_trait]
+impl JavaScanSource for UserPathSource {
+    async fn candidates(&self) -> Vec<PathBuf> {
+        let mut paths = vec![];
+        for path in &self.paths {
+            if is_file(path).await {
+                paths.push(path.clone());
+            } else {
+                paths.extend(java_in_home_candidates(path));
+            }
+        }
+        paths
+    }
+
+    fn source(&self) -> JavaSource {
+        JavaSource::User
+    }
+}
+
+// sdkman, asdf, jabba, gradle toolchains and jetbrains downloads
+fn tool_managed_dirs() -> Vec<PathBuf> {
+    let Some(home) = dirs::home_dir() else {
         return vec![];
     };
-    let binary = binary_name();
+    vec![
+        home.join(".sdkman").join("candidates").join("java"),
+        home.join(".asdf").join("installs").join("java"),
+        home.join(".jabba").join("jdk"),
+        home.join(".gradle").join("jdks"),
+        home.join(".jdks"),
+    ]
+}
 
-    let mut paths = vec![
-        cwd.join(binary),
-        cwd.join("bin").join(binary),
-        cwd.join("java").join("bin").join(binary),
-        cwd.join("jre").join("bin").join(binary),
-    ];
+fn default_minecraft_dir() -> Option<PathBuf> {
+    if cfg!(windows) {
+        dirs::config_dir().map(|dir| dir.join(".minecraft"))
+    } else if cfg!(target_os = "macos") {
+        dirs::data_dir().map(|dir| dir.join("minecraft"))
+    } else {
+        dirs::home_dir().map(|dir| dir.join(".minecraft"))
+    }
+}
 
-    let runtime_dir = cwd.join("runtime");
-    if let Ok(mut entries) = read_dir(&runtime_dir).await {
-        while let Ok(Some(entry)) = entries.next_entry().await {
-            let Ok(file_type) = entry.file_type().await else {
-                continue;
-            };
+async fn scan_java_homes_dir(dir: &Path) -> Vec<PathBuf> {
+    sub_dirs(dir)
+        .await
+        .iter()
+        .flat_map(|home| java_in_home_candidates(home))
+        .collect()
+}
 
-            if file_type.is_dir() {
-                paths.push(entry.path().join("bin").join(binary));
+async fn sub_dirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![];
+    if let Ok(mut entries) = read_dir(dir).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            // follows symlinks, sdkman's `current` is one
+            if tokio::fs::metadata(entry.path())
+                .await
+                .is_ok_and(|m| m.is_dir())
+            {
+                dirs.push(entry.path());
             }
         }
     }
+    dirs
+}
 
-    paths
+// macOS bundles keep the java home in Contents/Home
+fn java_in_home_candidates(home: &Path) -> [PathBuf; 2] {
+    [
+        java_in_home(home),
+        java_in_home(&home.join("Contents").join("Home")),
+    ]
+}
+
+pub fn java_in_home(home: &Path) -> PathBuf {
+    home.join("bin").join(binary_name())
 }
 
 fn binary_name() -> &'static str {
     if cfg!(windows) { "java.exe" } else { "java" }
 }
 
-async fn is_executable(path: PathBuf) -> bool {
-    tokio::fs::metadata(path).await.is_ok()
+async fn is_file(path: &Path) -> bool {
+    tokio::fs::metadata(path).await.is_ok_and(|m| m.is_file())
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn fake_java_home(home: &Path) -> PathBuf {
+        let java = java_in_home(home);
+        std::fs::create_dir_all(java.parent().unwrap()).unwrap();
+        std::fs::write(&java, b"").unwrap();
+        java.canonicalize().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_finds_minecraft_runtimes_and_java_homes() {
+        let root = TempDir::new().unwrap();
+        let mojang = fake_java_home(
+            &root
+                .path()
+                .join(".minecraft/runtime/java-runtime-gamma/linux/java-runtime-gamma"),
+        );
+        let jvm = fake_java_home(&root.path().join("jvm/temurin-21"));
+        let sources: Vec<Box<dyn JavaScanSource>> = vec![
+            Box::new(MinecraftRuntimeSource {
+                minecraft_dirs: vec![root.path().join(".minecraft")],
+            }),
+            Box::new(JavaHomesDirSource {
+                dirs: vec![root.path().join("jvm"), root.path().join("missing")],
+            }),
+        ];
+
+        assert_eq!(
+            candidate_paths(&sources).await,
+            vec![(mojang, JavaSource::System), (jvm, JavaSource::System)]
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_deduplicates_symlinked_java() {
+        let root = TempDir::new().unwrap();
+        let java = fake_java_home(&root.path().join("candidates/17.0.8-tem"));
+        std::os::unix::fs::symlink(
+            root.path().join("candidates/17.0.8-tem"),
+            root.path().join("candidates/current"),
+        )
+        .unwrap();
+        let sources: Vec<Box<dyn JavaScanSource>> = vec![
+            Box::new(JavaHomesDirSource {
+                dirs: vec![root.path().join("candidates")],
+            }),
+            Box::new(UserPathSource {
+                paths: vec![
+                    root.path().join("candidates/current"),
+                    root.path().join("candidates/current/bin/java"),
+                ],
+            }),
+        ];
+
+        assert_eq!(
+            candidate_paths(&sources).await,
+            vec![(java, JavaSource::System)]
+        );
+    }
 }
@@ -0,0 +1,252 @@
+use crate::game_launcher::models::RuleContext;
+use crate::game_resolver::models::JavaVersion;
+use crate::java_runtime::models::{JavaArch, JavaInstance};
+use crate::utils::os_info::is_macos;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct JavaRequirement {
+    pub major_version: u32,
+    pub component: String,
+    // most preferred first
+    pub archs: Vec<JavaArch>,
+}
+
+impl JavaRequirement {
+    pub fn new(java_version: &JavaVersion, rule_context: &RuleContext) -> Self {
+        JavaRequirement {
+            major_version: java_version.major_version,
+            component: java_version.component.clone(),
+            archs: runnable_archs(rule_context),
+        }
+    }
+
+    // launchwrapper and legacy forge crash on java 9+, so java 8 is never upgraded
+    fn accepts(&self, major_version: u32) -> bool {
+        if self.major_version <= 8 {
+            major_version == 8
+        } else {
+            major_version >= self.major_version
+        }
+    }
+
+    pub fn is_met_by(&self, java: &JavaInstance) -> bool {
+        self.accepts(java.major_version) && self.arch_rank(&java.arch).is_some()
+    }
+
+    fn arch_rank(&self, arch: &JavaArch) -> Option<usize> {
+        self.archs.iter().position(|a| a == arch)
+    }
+}
+
+/// Java architectures the host can run, 64-bit native builds first.
+pub fn runnable_archs(rule_context: &RuleContext) -> Vec<JavaArch> {
+    let arch = rule_context
+        .os_info
+        .architecture()
+        .unwrap_or_default()
+        .to_lowercase();
+    match arch.as_str() {
+        "aarch64" | "arm64" if is_macos(&rule_context.os_info) => {
+            vec![JavaArch::Arm64, JavaArch::X64]
+        }
+        "aarch64" | "arm64" => vec![JavaArch::Arm64],
+        "x86_64" | "amd64" | "x64" => vec![JavaArch::X64, JavaArch::X86],
+        "x86" | "i386" | "i586" | "i686" => vec![JavaArch::X86],
+        _ => vec![JavaArch::X64, JavaArch::Arm64, JavaArch::X86],
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct JavaDownloadOffer {
+    pub major_version: u32,
+    pub component: String,
+    pub arch: JavaArch,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+#[error("No installed java runtime can run java {} on {:?}", .offer.major_version, .offer.arch)]
+pub struct JavaSelectionError {
+    pub offer: JavaDownloadOffer,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum JavaSelection {
+    Found { java: JavaInstance },
+    DownloadRequired { offer: JavaDownloadOffer },
+}
+
+impl From<Result<JavaInstance, JavaSelectionError>> for JavaSelection {
+    fn from(result: Result<JavaInstance, JavaSelectionError>) -> Self {
+        match result {
+            Ok(java) => JavaSelection::Found { java },
+            Err(e) => JavaSelection::DownloadRequired { offer: e.offer },
+        }
+    }
+}
+
+/// Picks the closest major version that is new enough (exactly java 8 for java 8 versions),
+/// then the preferred arch, then the first vendor in `vendor_preference`.
+pub fn select_java(
+    instances: Vec<JavaInstance>,
+    requirement: &JavaRequirement,
+    vendor_preference: &[String],
+) -> Result<JavaInstance, JavaSelectionError> {
+    instances
+        .into_iter()
+        .filter(|java| requirement.accepts(java.major_version))
+        .filter_map(|java| {
+            let arch_rank = requirement.arch_rank(&java.arch)?;
+            let major_distance = java.major_version.abs_diff(requirement.major_version);
+            let vendor_rank = vendor_rank(&java.vendor_name, vendor_preference);
+            Some(((major_distance, arch_rank, vendor_rank), java))
+        })
+        .min_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, java)| java)
+        .ok_or_else(|| JavaSelectionError {
+            offer: JavaDownloadOffer {
+                major_version: requirement.major_version,
+                component: requirement.component.clone(),
+                arch: requirement.archs.first().cloned().unwrap_or(JavaArch::X64),
+            },
+        })
+}
+
+fn vendor_rank(vendor_name: &str, vendor_preference: &[String]) -> usize {
+    let vendor_name = vendor_name.to_lowercase();
+    vendor_preference
+        .iter()
+        .position(|preferred| vendor_name.contains(&preferred.to_lowercase()))
+        .unwrap_or(vendor_preference.len())
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::java_runtime::models::{JavaSource, JavaVersionParts};
+    use crate::utils::os_info::mock_info;
+    use os_info::Type;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn java(major_version: u32, arch: JavaArch, vendor_name: &str) -> JavaInstance {
+        let java_home = PathBuf::from(format!("/usr/lib/jvm/java-{major_version}"));
+        JavaInstance {
+            executable_path: java_home.join("bin/java"),
+            java_home,
+            version: major_version.to_string(),
+            version_parts: JavaVersionParts {
+                major: major_version,
+                ..Default::default()
+            },
+            major_version,
+            is_64bit: arch.is_64bit(),
+            arch,
+            vendor_name: vendor_name.to_string(),
+            is_jdk: true,
+            has_javaw: false,
+            source: JavaSource::System,
+        }
+    }
+
+    fn requirement(major_version: u32, os_type: Type, arch: &str) -> JavaRequirement {
+        let rule_context = RuleContext {
+            os_info: mock_info(os_type, "1.0", arch),
+            user_features: HashMap::new(),
+        };
+        let java_version = JavaVersion {
+            component: "java-runtime-delta".to_string(),
+            major_version,
+        };
+        JavaRequirement::new(&java_version, &rule_context)
+    }
+
+    #[test]
+    fn test_prefers_the_closest_compatible_major_version() {
+        let installed = || {
+            vec![
+                java(21, JavaArch::X64, "Eclipse Temurin"),
+                java(8, JavaArch::X64, "Eclipse Temurin"),
+                java(17, JavaArch::X64, "Eclipse Temurin"),
+            ]
+        };
+        let select = |major| {
+            select_java(installed(), &requirement(major, Type::Linux, "x86_64"), &[])
+                .map(|java| java.major_version)
+        };
+
+        assert_eq!(select(17), Ok(17));
+        assert_eq!(select(8), Ok(8));
+        assert_eq!(select(16), Ok(17));
+        assert!(select(25).is_err());
+    }
+
+    #[test]
+    fn test_never_upgrades_java_8() {
+        let select = |installed: Vec<u32>, major| {
+            let installed = installed
+                .into_iter()
+                .map(|major| java(major, JavaArch::X64, "Eclipse Temurin"))
+                .collect();
+            select_java(installed, &requirement(major, Type::Linux, "x86_64"), &[])
+                .map(|java| java.major_version)
+        };
+
+        assert_eq!(select(vec![17, 8], 8), Ok(8));
+        assert!(select(vec![17, 21], 8).is_err());
+        assert_eq!(select(vec![17, 8], 7), Ok(8));
+    }
+
+    #[test]
+    fn test_requirement_checks_a_given_java() {
+        let modern = requirement(21, Type::Linux, "x86_64");
+        let legacy = requirement(8, Type::Linux, "x86_64");
+
+        assert!(modern.is_met_by(&java(21, JavaArch::X64, "Eclipse Temurin")));
+        assert!(!modern.is_met_by(&java(8, JavaArch::X64, "Eclipse Temurin")));
+        assert!(!modern.is_met_by(&java(21, JavaArch::Arm64, "Eclipse Temurin")));
+        assert!(!legacy.is_met_by(&java(17, JavaArch::X64, "Eclipse Temurin")));
+    }
+
+    #[test]
+    fn test_prefers_64_bit_then_vendor_preference() {
+        let installed = vec![
+            java(17, JavaArch::X86, "Azul Zulu"),
+            java(17, JavaArch::X64, "Oracle Java"),
+            java(17, JavaArch::X64, "Azul Zulu"),
+        ];
+        let preference = ["zulu".to_string()];
+
+        let selected = select_java(
+            installed,
+            &requirement(17, Type::Linux, "x86_64"),
+            &preference,
+        )
+        .unwrap();
+
+        assert_eq!(selected, java(17, JavaArch::X64, "Azul Zulu"));
+    }
+
+    #[test]
+    fn test_skips_runtimes_the_host_cannot_run() {
+        let installed = || vec![java(21, JavaArch::X64, "Eclipse Temurin")];
+
+        let linux = select_java(installed(), &requirement(21, Type::Linux, "aarch64"), &[]);
+        let macos = select_java(installed(), &requirement(21, Type::Macos, "arm64"), &[]);
+
+        assert_eq!(
+            linux.unwrap_err().offer,
+            JavaDownloadOffer {
+                major_version: 21,
+                component: "java-runtime-delta".to_string(),
+                arch: JavaArch::Arm64,
+            }
+        );
+        assert!(macos.is_ok(), "Rosetta runs x64 java on Apple silicon");
+    }
+}
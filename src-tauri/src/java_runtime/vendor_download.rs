@@ -0,0 +1,644 @@
+use crate::game_installer::download::{DownloadError, download_file, fetch_json, file_sha256};
+use crate::java_runtime::inspector::{detect_vendor, inspect_java_executable};
+use crate::java_runtime::models::{JavaArch, JavaInstance, JavaSource};
+use crate::java_runtime::scanner::java_in_home;
+use crate::scheduler::{Task, task};
+use crate::utils::abs_path_buf::AbsPathBuf;
+use anyhow::{Context, Result, anyhow, bail};
+use flate2::read::GzDecoder;
+use log::info;
+use os_info::Type as OsType;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Hash, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum JavaVendor {
+    Temurin,
+    Zulu,
+    Liberica,
+}
+
+impl JavaVendor {
+    // looked up in VENDOR_KEYWORDS_MAP for the display name
+    fn keyword(&self) -> &'static str {
+        match self {
+            JavaVendor::Temurin => "temurin",
+            JavaVendor::Zulu => "zulu",
+            JavaVendor::Liberica => "liberica",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VendorEndpoints {
+    pub adoptium_api_url: String,
+    pub zulu_api_url: String,
+    pub liberica_api_url: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VendorJdkQuery {
+    pub vendor: JavaVendor,
+    pub major_version: u32,
+    pub arch: JavaArch,
+    pub os_type: OsType,
+}
+
+impl VendorJdkQuery {
+    fn is_windows(&self) -> bool {
+        self.os_type == OsType::Windows
+    }
+
+    fn archive_type(&self) -> &'static str {
+        if self.is_windows() { "zip" } else { "tar.gz" }
+    }
+
+    /// Directory name under the runtime dir, e.g. `temurin-17-x64`.
+    pub fn install_name(&self) -> String {
+        let arch = match &self.arch {
+            JavaArch::X86 => "x86",
+            JavaArch::X64 => "x64",
+            JavaArch::Arm64 => "arm64",
+            JavaArch::Unknown(arch) => arch,
+        };
+        format!("{}-{}-{arch}", self.vendor.keyword(), self.major_version)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum VendorChecksum {
+    Sha256(String),
+    Sha1(String),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VendorJdkRelease {
+    pub version: String,
+    pub url: String,
+    pub file_name: String,
+    pub checksum: VendorChecksum,
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdoptiumAsset {
+    binary: AdoptiumBinary,
+    release_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdoptiumBinary {
+    package: AdoptiumPackage,
+}
+
+#[derive(Debug, Deserialize)]
+struct AdoptiumPackage {
+    checksum: String,
+    link: String,
+    name: String,
+    size: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ZuluPackage {
+    package_uuid: String,
+    name: String,
+    download_url: String,
+    java_version: Vec<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ZuluPackageDetails {
+    sha256_hash: String,
+    size: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LibericaRelease {
+    download_url: String,
+    filename: String,
+    sha1: String,
+    size: Option<u64>,
+    version: String,
+}
+
+pub async fn find_vendor_release(
+    client: &Client,
+    endpoints: &VendorEndpoints,
+    query: &VendorJdkQuery,
+) -> Result<VendorJdkRelease> {
+    match query.vendor {
+        JavaVendor::Temurin => {
+            find_temurin_release(client, &endpoints.adoptium_api_url, query).await
+        }
+        JavaVendor::Zulu => find_zulu_release(client, &endpoints.zulu_api_url, query).await,
+        JavaVendor::Liberica => {
+            find_liberica_release(client, &endpoints.liberica_api_url, query).await
+        }
+    }
+}
+
+async fn find_temurin_release(
+    client: &Client,
+    base_url: &str,
+    query: &VendorJdkQuery,
+) -> Result<VendorJdkRelease> {
+    let os = match query.os_type {
+        OsType::Windows => "windows",
+        OsType::Macos => "mac",
+        _ => "linux",
+    };
+    let arch = match query.arch {
+        JavaArch::X86 => "x32",
+        JavaArch::Arm64 => "aarch64",
+        _ => "x64",
+    };
+    let url = format!(
+        "{}/v3/assets/latest/{}/hotspot?architecture={arch}&image_type=jdk&os={os}&vendor=eclipse",
+        base_url.trim_end_matches('/'),
+        query.major_version
+    );
+    let assets: Vec<AdoptiumAsset> = fetch_json(client, &url, None).await?;
+    let asset = assets
+        .into_iter()
+        .find(|asset| asset.binary.package.name.ends_with(query.archive_type()))
+        .ok_or_else(|| no_release_error(query))?;
+    let package = asset.binary.package;
+    Ok(VendorJdkRelease {
+        version: asset.release_name,
+        url: package.link,
+        file_name: package.name,
+        checksum: VendorChecksum::Sha256(package.checksum),
+        size: Some(package.size),
+    })
+}
+
+async fn find_zulu_release(
+    client: &Client,
+    base_url: &str,
+    query: &VendorJdkQuery,
+) -> Result<VendorJdkRelease> {
+    let os = match query.os_type {
+        OsType::Windows => "windows",
+        OsType::Macos => "macos",
+        _ => "linux",
+    };
+    let arch = match query.arch {
+        JavaArch::X86 => "i686",
+        JavaArch::Arm64 => "aarch64",
+        _ => "x64",
+    };
+    let base_url = base_url.trim_end_matches('/');
+    let url = format!(
+        "{base_url}/metadata/v1/zulu/packages/?java_version={}&os={os}&arch={arch}&archive_type={}&java_package_type=jdk&javafx_bundled=false&release_status=ga&latest=true",
+        query.major_version,
+        query.archive_type()
+    );
+    let packages: Vec<ZuluPackage> = fetch_json(client, &url, None).await?;
+    let package = packages
+        .into_iter()
+        .next()
+        .ok_or_else(|| no_release_error(query))?;
+    // the checksum is only part of the package details
+    let details_url = format!(
+        "{base_url}/metadata/v1/zulu/packages/{}",
+        package.package_uuid
+    );
+    let details: ZuluPackageDetails = fetch_json(client, &details_url, None).await?;
+    let version = package
+        .java_version
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(".");
+    Ok(VendorJdkRelease {
+        version,
+        url: package.download_url,
+        file_name: package.name,
+        checksum: VendorChecksum::Sha256(details.sha256_hash),
+        size: details.size,
+    })
+}
+
+async fn find_liberica_release(
+    client: &Client,
+    base_url: &str,
+    query: &VendorJdkQuery,
+) -> Result<VendorJdkRelease> {
+    let os = match query.os_type {
+        OsType::Windows => "windows",
+        OsType::Macos => "macos",
+        _ => "linux",
+    };
+    let (arch, bitness) = match query.arch {
+        JavaArch::X86 => ("x86", 32),
+        JavaArch::Arm64 => ("arm", 64),
+        _ => ("x86", 64),
+    };
+    let url = format!(
+        "{}/v1/liberica/releases?version-feature={}&version-modifier=latest&bitness={bitness}&os={os}&arch={arch}&package-type={}&bundle-type=jdk",
+        base_url.trim_end_matches('/'),
+        query.major_version,
+        query.archive_type()
+    );
+    let releases: Vec<LibericaRelease> = fetch_json(client, &url, None).await?;
+    let release = releases
+        .into_iter()
+        .next()
+        .ok_or_else(|| no_release_error(query))?;
+    Ok(VendorJdkRelease {
+        version: release.version,
+        url: release.download_url,
+        file_name: release.filename,
+        checksum: VendorChecksum::Sha1(release.sha1),
+        size: release.size,
+    })
+}
+
+fn no_release_error(query: &VendorJdkQuery) -> anyhow::Error {
+    anyhow!(
+        "{:?} does not publish java {} for {:?} {:?}",
+        query.vendor,
+        query.major_version,
+        query.os_type,
+        query.arch
+    )
+}
+
+/// Downloads and unpacks a vendor JDK into `runtime_dir/<install name>`.
+pub fn install_vendor_jdk_task(
+    client: Client,
+    endpoints: VendorEndpoints,
+    query: VendorJdkQuery,
+    runtime_dir: AbsPathBuf,
+) -> impl Task<Input = (), Output = JavaInstance> {
+    let name = format!("Install {:?} {}", query.vendor, query.major_version);
+    task(name, move |_: ()| {
+        install_vendor_jdk(
+            client.clone(),
+            endpoints.clone(),
+            query.clone(),
+            runtime_dir.clone(),
+        )
+    })
+}
+
+async fn install_vendor_jdk(
+    client: Client,
+    endpoints: VendorEndpoints,
+    query: VendorJdkQuery,
+    runtime_dir: AbsPathBuf,
+) -> Result<JavaInstance> {
+    let release = find_vendor_release(&client, &endpoints, &query).await?;
+    info!(
+        "Installing {:?} {} from {}",
+        query.vendor, release.version, release.url
+    );
+    let archive_path = runtime_dir.join(&release.file_name);
+    let sha1 = match &release.checksum {
+        VendorChecksum::Sha1(sha1) => Some(sha1.as_str()),
+        VendorChecksum::Sha256(_) => None,
+    };
+    download_file(&client, &release.url, &archive_path, sha1, release.size).await?;
+    if let VendorChecksum::Sha256(expected) = &release.checksum {
+        let actual = file_sha256(&archive_path).await?;
+        if !expected.eq_ignore_ascii_case(&actual) {
+            let _ = tokio::fs::remove_file(&archive_path).await;
+            return Err(DownloadError::ChecksumMismatch {
+                url: release.url,
+                expected: expected.clone(),
+                actual,
+            }
+            .into());
+        }
+    }
+
+    let install_dir = runtime_dir.join(query.install_name());
+    let (archive, dest) = (archive_path.to_path_buf(), install_dir.to_path_buf());
+    let result = tokio::task::spawn_blocking(move || extract_jdk_archive(&archive, &dest)).await?;
+    let _ = tokio::fs::remove_file(&archive_path).await;
+    result.with_context(|| format!("Failed to extract {}", release.file_name))?;
+
+    let java = find_java_home(&install_dir)
+        .map(|home| java_in_home(&home))
+        .ok_or_else(|| anyhow!("No java found in {}", release.file_name))?;
+    let mut instance = inspect_java_executable(java)
+        .await
+        .ok_or_else(|| anyhow!("Installed {:?} jdk cannot be started", query.vendor))?;
+    instance.vendor_name = detect_vendor(query.vendor.keyword().to_string());
+    instance.source = JavaSource::Managed;
+    Ok(instance)
+}
+
+/// Unpacks a tar.gz or zip archive into `dest`, dropping the single top-level directory
+/// the vendors wrap their builds in.
+pub fn extract_jdk_archive(archive: &Path, dest: &Path) -> Result<()> {
+    let mut staging = dest.as_os_str().to_owned();
+    staging.push(".part");
+    let staging = PathBuf::from(staging);
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    fs::create_dir_all(&staging)?;
+
+    let file_name = archive.to_string_lossy().to_lowercase();
+    if file_name.ends_with(".zip") {
+        extract_zip(archive, &staging)?;
+    } else if file_name.ends_with(".tar.gz") || file_name.ends_with(".tgz") {
+        extract_tar_gz(archive, &staging)?;
+    } else {
+        bail!("Unsupported archive {}", archive.display());
+    }
+
+    if dest.exists() {
+        fs::remove_dir_all(dest)?;
+    }
+    fs::rename(&staging, dest)?;
+    Ok(())
+}
+
+fn strip_top_dir(path: &Path) -> Option<PathBuf> {
+    let mut components = path.components();
+    components.next()?;
+    let stripped: PathBuf = components.collect();
+    let is_safe = stripped
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)));
+    (is_safe && !stripped.as_os_str().is_empty()).then_some(stripped)
+}
+
+fn extract_tar_gz(archive: &Path, dest: &Path) -> Result<()> {
+    let root = dest.canonicalize()?;
+    let mut tar = tar::Archive::new(GzDecoder::new(File::open(archive)?));
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.to_path_buf();
+        let Some(relative_path) = strip_top_dir(&entry_path) else {
+            continue;
+        };
+        let target = root.join(relative_path);
+        let parent = target.parent().unwrap_or(&root);
+        fs::create_dir_all(parent)?;
+        // an earlier link entry must not redirect this write out of the archive root
+        if !parent.canonicalize()?.starts_with(&root) {
+            bail!("{} escapes the archive root", entry_path.display());
+        }
+
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_symlink() || entry_type.is_hard_link() {
+            let link_name = entry
+                .link_name()?
+                .ok_or_else(|| anyhow!("{} has no link target", entry_path.display()))?
+                .to_path_buf();
+            // hard link targets are archive paths, symlink targets are relative to the link
+            let link_target = if entry_type.is_hard_link() {
+                strip_top_dir(&link_name).map(|path| root.join(path))
+            } else {
+                resolve_link(&parent.canonicalize()?, &link_name)
+            };
+            let Some(link_target) = link_target.filter(|path| path.starts_with(&root)) else {
+                bail!(
+                    "{} links outside the archive root to {}",
+                    entry_path.display(),
+                    link_name.display()
+                );
+            };
+            if entry_type.is_hard_link() {
+                fs::hard_link(link_target, &target)?;
+                continue;
+            }
+        }
+        entry.unpack(&target)?;
+    }
+    Ok(())
+}
+
+// only leading `..` are allowed, so the lexical result matches what the os resolves
+fn resolve_link(base: &Path, link_name: &Path) -> Option<PathBuf> {
+    let mut resolved = base.to_path_buf();
+    let mut components = link_name.components().peekable();
+    while components.next_if_eq(&Component::ParentDir).is_some() {
+        resolved.pop();
+    }
+    for component in components {
+        match component {
+            Component::Normal(name) => resolved.push(name),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(resolved)
+}
+
+fn extract_zip(archive: &Path, dest: &Path) -> Result<()> {
+    let mut zip = zip::ZipArchive::new(File::open(archive)?)?;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        let Some(relative_path) = entry.enclosed_name().as_deref().and_then(strip_top_dir) else {
+            continue;
+        };
+        let target = dest.join(relative_path);
+        if entry.is_dir() {
+            fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut entry, &mut File::create(&target)?)?;
+        #[cfg(unix)]
+        if let Some(mode) = entry.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&target, fs::Permissions::from_mode(mode))?;
+        }
+    }
+    Ok(())
+}
+
+// macOS builds keep the java home inside a bundle
+fn find_java_home(install_dir: &Path) -> Option<PathBuf> {
+    [
+        install_dir.to_path_buf(),
+        install_dir.join("Contents").join("Home"),
+    ]
+    .into_iter()
+    .find(|home| java_in_home(home).is_file())
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+    use crate::scheduler::Scheduler;
+    use crate::utils::test_server::TestServer;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use serde_json::json;
+    use sha2::{Digest, Sha256};
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    const JAVA_SCRIPT: &[u8] = b"#!/bin/sh\necho 'openjdk version \"17.0.8\" 2023-07-18' >&2\n";
+
+    fn jdk_tar_gz() -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(vec![], Compression::fast()));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(JAVA_SCRIPT.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "jdk-17.0.8+7/bin/java", JAVA_SCRIPT)
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn endpoints(base_url: &str) -> VendorEndpoints {
+        VendorEndpoints {
+            adoptium_api_url: base_url.to_string(),
+            zulu_api_url: base_url.to_string(),
+            liberica_api_url: base_url.to_string(),
+        }
+    }
+
+    fn query(vendor: JavaVendor) -> VendorJdkQuery {
+        VendorJdkQuery {
+            vendor,
+            major_version: 17,
+            arch: JavaArch::X64,
+            os_type: OsType::Linux,
+        }
+    }
+
+    #[test]
+    fn test_strip_top_dir() {
+        assert_eq!(
+            strip_top_dir(Path::new("jdk-17/bin/java")),
+            Some(PathBuf::from("bin/java"))
+        );
+        assert_eq!(strip_top_dir(Path::new("jdk-17")), None);
+        assert_eq!(strip_top_dir(Path::new("jdk-17/../../etc/passwd")), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_extract_tar_gz_rejects_links_out_of_the_root() {
+        let archive_with_link = |link_path: &str, link_target: &str| {
+            let mut builder = tar::Builder::new(GzEncoder::new(vec![], Compression::fast()));
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_size(0);
+            builder
+                .append_link(&mut header, link_path, link_target)
+                .unwrap();
+            let mut header = tar::Header::new_gnu();
+            header.set_size(JAVA_SCRIPT.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, "jdk-17/lib/escape/file", JAVA_SCRIPT)
+                .unwrap();
+            builder.into_inner().unwrap().finish().unwrap()
+        };
+        let root = TempDir::new().unwrap();
+        let outside = root.path().join("outside");
+        fs::create_dir_all(&outside).unwrap();
+        let extract = |name: &str, archive: Vec<u8>| {
+            let archive_path = root.path().join(format!("{name}.tar.gz"));
+            fs::write(&archive_path, archive).unwrap();
+            extract_jdk_archive(&archive_path, &root.path().join(name))
+        };
+
+        let absolute = archive_with_link("jdk-17/lib/escape", &outside.to_string_lossy());
+        let relative = archive_with_link("jdk-17/lib/escape", "../../../outside");
+        let inside = archive_with_link("jdk-17/lib/escape", ".");
+
+        assert!(extract("absolute", absolute).is_err());
+        assert!(extract("relative", relative).is_err());
+        assert!(fs::read_dir(&outside).unwrap().next().is_none());
+        extract("inside", inside).unwrap();
+        assert!(root.path().join("inside/lib/file").is_file());
+    }
+
+    #[tokio::test]
+    async fn test_find_zulu_release_reads_checksum_from_details() {
+        let server = TestServer::start(HashMap::from([
+            (
+                "/metadata/v1/zulu/packages/?java_version=17&os=linux&arch=x64&archive_type=tar.gz&java_package_type=jdk&javafx_bundled=false&release_status=ga&latest=true".to_string(),
+                json!([{
+                    "package_uuid": "abc",
+                    "name": "zulu17.44.15-ca-jdk17.0.8-linux_x64.tar.gz",
+                    "download_url": "https://cdn.azul.com/zulu17.tar.gz",
+                    "java_version": [17, 0, 8]
+                }])
+                .to_string()
+                .into_bytes(),
+            ),
+            (
+                "/metadata/v1/zulu/packages/abc".to_string(),
+                json!({ "sha256_hash": "ff", "size": 10 }).to_string().into_bytes(),
+            ),
+        ]))
+        .await;
+
+        let release = find_vendor_release(
+            &Client::new(),
+            &endpoints(&server.base_url),
+            &query(JavaVendor::Zulu),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(release.version, "17.0.8");
+        assert_eq!(release.checksum, VendorChecksum::Sha256("ff".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_install_temurin_jdk() {
+        let archive = jdk_tar_gz();
+        let file_name = "OpenJDK17U-jdk_x64_linux_hotspot_17.0.8_7.tar.gz";
+        let assets = |base_url: &str| {
+            json!([{
+                "binary": { "package": {
+                    "checksum": hex::encode(Sha256::digest(&archive)),
+                    "link": format!("{base_url}/{file_name}"),
+                    "name": file_name,
+                    "size": archive.len()
+                }},
+                "release_name": "jdk-17.0.8+7"
+            }])
+            .to_string()
+        };
+        let server =
+            TestServer::start(HashMap::from([(format!("/{file_name}"), archive.clone())])).await;
+        let api = TestServer::start(HashMap::from([(
+            "/v3/assets/latest/17/hotspot?architecture=x64&image_type=jdk&os=linux&vendor=eclipse"
+                .to_string(),
+            assets(&server.base_url).into_bytes(),
+        )]))
+        .await;
+        let root = TempDir::new().unwrap();
+        let runtime_dir = AbsPathBuf::new(root.path()).unwrap();
+        let task = install_vendor_jdk_task(
+            Client::new(),
+            endpoints(&api.base_url),
+            query(JavaVendor::Temurin),
+            runtime_dir.clone(),
+        );
+
+        let java = Scheduler::new(8).run(task).await.unwrap();
+
+        assert_eq!(java.major_version, 17);
+        assert_eq!(java.vendor_name, "Eclipse Temurin");
+        assert_eq!(java.source, JavaSource::Managed);
+        assert!(runtime_dir.join("temurin-17-x64/bin/java").is_file());
+        assert!(!runtime_dir.join(file_name).exists());
+    }
+}
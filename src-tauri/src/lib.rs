@@ -4,17 +4,21 @@
 mod auth;
 mod constants;
 mod game_assets;
+mod game_installer;
 mod game_launcher;
 mod game_resolver;
 mod i18n;
 mod ipc;
 mod java_runtime;
+mod mod_loader;
 mod scheduler;
 mod settings;
 mod theme;
 pub mod utils;
 
 use crate::constants::file_system::LOG_DIR_NAME;
+use crate::game_installer::commands::setup_game_installer;
+use crate::game_launcher::commands::setup_game_launcher;
 use crate::ipc::command::command_handler;
 use crate::scheduler::commands::setup_scheduler;
 use crate::settings::commands::setup_settings;
@@ -90,5 +94,7 @@ async fn setup_app(app: &mut App) -> Result<()> {
     setup_settings(app).await?;
     setup_theme(app)?;
     setup_scheduler(app);
+    setup_game_installer(app)?;
+    setup_game_launcher(app)?;
     Ok(())
 }
@@ -0,0 +1,182 @@
+use crate::game_installer::commands::install_libraries;
+use crate::game_launcher::models::{RuleContext, infer_java_version};
+use crate::game_resolver::VersionManifest;
+use crate::game_resolver::resolve::{resolve_installed_version, resolve_minecraft_folder};
+use crate::java_runtime::scanner::scan_all;
+use crate::java_runtime::selector::{JavaRequirement, select_java};
+use crate::mod_loader::fabric::{FabricLoaderVersion, FabricMetaClient};
+use crate::mod_loader::forge::ForgeInstaller;
+use crate::mod_loader::optifine::{OptiFineInstaller, OptiFineModInstall};
+use crate::mod_loader::quilt::{QuiltLoaderVersion, QuiltMetaClient};
+use crate::scheduler::Scheduler;
+use crate::settings::modules::download::DownloadSettings;
+use crate::settings::modules::java::JavaSettings;
+use crate::settings::store::SettingsStore;
+use crate::utils::abs_path_buf::AbsPathBuf;
+use crate::utils::command::CommandResult;
+use anyhow::Result;
+use macros::command;
+use reqwest::Client;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::State;
+
+fn fabric_meta(store: &SettingsStore) -> FabricMetaClient {
+    FabricMetaClient::new(
+        Client::new(),
+        store.get::<DownloadSettings>().fabric_meta_url,
+    )
+}
+
+fn quilt_meta(store: &SettingsStore) -> QuiltMetaClient {
+    QuiltMetaClient::new(
+        Client::new(),
+        store.get::<DownloadSettings>().quilt_meta_url,
+    )
+}
+
+#[command]
+pub async fn list_fabric_loaders(
+    store: State<'_, Arc<SettingsStore>>,
+    minecraft_dir: String,
+    version_id: String,
+) -> CommandResult<Vec<FabricLoaderVersion>> {
+    let minecraft_dir = AbsPathBuf::new(&minecraft_dir)?;
+    let vanilla = resolve_installed_version(minecraft_dir, &version_id).await?;
+    Ok(fabric_meta(&store).list_loader_versions(&vanilla).await?)
+}
+
+#[command]
+pub async fn install_fabric(
+    store: State<'_, Arc<SettingsStore>>,
+    scheduler: State<'_, Scheduler>,
+    minecraft_dir: String,
+    version_id: String,
+    loader_version: String,
+) -> CommandResult<String> {
+    let minecraft_dir = AbsPathBuf::new(&minecraft_dir)?;
+    let vanilla = resolve_installed_version(minecraft_dir.clone(), &version_id).await?;
+    let manifest = fabric_meta(&store)
+        .install(&minecraft_dir, &vanilla, &loader_version)
+        .await?;
+    install_libraries(&scheduler, &minecraft_dir, &manifest.libraries).await?;
+    Ok(manifest.id)
+}
+
+#[command]
+pub async fn list_quilt_loaders(
+    store: State<'_, Arc<SettingsStore>>,
+    minecraft_dir: String,
+    version_id: String,
+) -> CommandResult<Vec<QuiltLoaderVersion>> {
+    let minecraft_dir = AbsPathBuf::new(&minecraft_dir)?;
+    let vanilla = resolve_installed_version(minecraft_dir, &version_id).await?;
+    Ok(quilt_meta(&store).list_loader_versions(&vanilla).await?)
+}
+
+#[command]
+pub async fn install_quilt(
+    store: State<'_, Arc<SettingsStore>>,
+    scheduler: State<'_, Scheduler>,
+    minecraft_dir: String,
+    version_id: String,
+    loader_version: String,
+) -> CommandResult<String> {
+    let minecraft_dir = AbsPathBuf::new(&minecraft_dir)?;
+    let vanilla = resolve_installed_version(minecraft_dir.clone(), &version_id).await?;
+    let manifest = quilt_meta(&store)
+        .install(&minecraft_dir, &vanilla, &loader_version)
+        .await?;
+    install_libraries(&scheduler, &minecraft_dir, &manifest.libraries).await?;
+    Ok(manifest.id)
+}
+
+#[command]
+pub async fn install_forge(
+    store: State<'_, Arc<SettingsStore>>,
+    scheduler: State<'_, Scheduler>,
+    minecraft_dir: String,
+    version_id: String,
+    installer_path: String,
+    java_path: Option<String>,
+) -> CommandResult<String> {
+    let minecraft_dir = AbsPathBuf::new(&minecraft_dir)?;
+    let vanilla = resolve_installed_version(minecraft_dir.clone(), &version_id).await?;
+    let java = match java_path {
+        Some(path) => PathBuf::from(path),
+        None => installer_java(&store, &minecraft_dir, &vanilla).await?,
+    };
+    let installer = ForgeInstaller::open(PathBuf::from(installer_path)).await?;
+    let manifest = installer
+        .install(&scheduler, &minecraft_dir, &vanilla, &java)
+        .await?;
+    install_libraries(&scheduler, &minecraft_dir, &manifest.libraries).await?;
+    Ok(manifest.id)
+}
+
+#[command]
+pub async fn install_optifine(
+    store: State<'_, Arc<SettingsStore>>,
+    scheduler: State<'_, Scheduler>,
+    minecraft_dir: String,
+    version_id: String,
+    installer_path: String,
+    java_path: Option<String>,
+) -> CommandResult<String> {
+    let minecraft_dir = AbsPathBuf::new(&minecraft_dir)?;
+    let vanilla = resolve_installed_version(minecraft_dir.clone(), &version_id).await?;
+    let java = match java_path {
+        Some(path) => PathBuf::from(path),
+        None => installer_java(&store, &minecraft_dir, &vanilla).await?,
+    };
+    let installer = OptiFineInstaller::open(PathBuf::from(installer_path)).await?;
+    let manifest = installer
+        .install_standalone(&minecraft_dir, &vanilla, &java)
+        .await?;
+    install_libraries(&scheduler, &minecraft_dir, &manifest.libraries).await?;
+    Ok(manifest.id)
+}
+
+#[command]
+pub async fn install_optifine_mod(
+    minecraft_dir: String,
+    version_id: String,
+    installer_path: String,
+) -> CommandResult<OptiFineModInstall> {
+    let minecraft_dir = AbsPathBuf::new(&minecraft_dir)?;
+    let instance = resolve_installed_version(minecraft_dir.clone(), &version_id).await?;
+    let game_dir = resolve_minecraft_folder(minecraft_dir)
+        .await
+        .game_dir(&version_id);
+    let installer = OptiFineInstaller::open(PathBuf::from(installer_path)).await?;
+    Ok(installer.install_as_mod(&game_dir, &instance).await?)
+}
+
+// installers run their tools on the same java the game would
+async fn installer_java(
+    store: &SettingsStore,
+    minecraft_dir: &AbsPathBuf,
+    vanilla: &VersionManifest,
+) -> Result<PathBuf> {
+    let settings = store.get::<JavaSettings>();
+    let rule_context = RuleContext {
+        os_info: os_info::get(),
+        user_features: HashMap::new(),
+    };
+    let java_version = vanilla
+        .java_version
+        .clone()
+        .unwrap_or_else(|| infer_java_version(&vanilla.release_time));
+    let requirement = JavaRequirement::new(&java_version, &rule_context);
+    let mut scan_options = settings.scan_options();
+    scan_options
+        .minecraft_dirs
+        .push(minecraft_dir.to_path_buf());
+    let java = select_java(
+        scan_all(&scan_options).await,
+        &requirement,
+        &settings.vendor_preference,
+    )?;
+    Ok(java.executable_path)
+}
@@ -0,0 +1,97 @@
+use crate::game_installer::download::fetch_json;
+use crate::game_resolver::VersionManifest;
+use crate::mod_loader::utils::{game_version, with_parent, write_child_version};
+use crate::utils::abs_path_buf::AbsPathBuf;
+use anyhow::Result;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FabricLoaderVersion {
+    pub version: String,
+    pub maven: String,
+    #[serde(default)]
+    pub stable: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct LoaderEntry {
+    loader: FabricLoaderVersion,
+}
+
+/// Client of the Fabric meta API, the base url can point to a mirror.
+/// Quilt serves the same protocol under a newer api version.
+pub struct FabricMetaClient {
+    client: Client,
+    base_url: String,
+    api_version: &'static str,
+}
+
+impl FabricMetaClient {
+    pub fn new(client: Client, base_url: impl Into<String>) -> Self {
+        Self::with_api_version(client, base_url, "v2")
+    }
+
+    pub(crate) fn with_api_version(
+        client: Client,
+        base_url: impl Into<String>,
+        api_version: &'static str,
+    ) -> Self {
+        Self {
+            client,
+            base_url: base_url.into(),
+            api_version,
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        let base_url = self.base_url.trim_end_matches('/');
+        format!("{base_url}/{}/{path}", self.api_version)
+    }
+
+    /// Loader versions that support the given vanilla version, newest first.
+    pub async fn list_loader_versions(
+        &self,
+        vanilla: &VersionManifest,
+    ) -> Result<Vec<FabricLoaderVersion>> {
+        let entries: Vec<LoaderEntry> = self.fetch_loader_entries(vanilla).await?;
+        Ok(entries.into_iter().map(|entry| entry.loader).collect())
+    }
+
+    /// Writes the loader profile as a child of `vanilla` and returns the new version.
+    /// Its libraries still have to be downloaded.
+    pub async fn install(
+        &self,
+        minecraft_dir: &AbsPathBuf,
+        vanilla: &VersionManifest,
+        loader_version: &str,
+    ) -> Result<VersionManifest> {
+        let profile = self.fetch_profile(vanilla, loader_version).await?;
+        write_child_version(minecraft_dir, profile).await
+    }
+
+    pub(crate) async fn fetch_loader_entries<T: DeserializeOwned>(
+        &self,
+        vanilla: &VersionManifest,
+    ) -> Result<Vec<T>> {
+        let url = self.url(&format!("versions/loader/{}", game_version(vanilla)));
+        fetch_json(&self.client, &url, None).await
+    }
+
+    pub(crate) async fn fetch_profile(
+        &self,
+        vanilla: &VersionManifest,
+        loader_version: &str,
+    ) -> Result<Value> {
+        let url = self.url(&format!(
+            "versions/loader/{}/{loader_version}/profile/json",
+            game_version(vanilla)
+        ));
+        let profile = fetch_json(&self.client, &url, None).await?;
+        Ok(with_parent(profile, vanilla))
+    }
+}
@@ -0,0 +1,434 @@
+use crate::constants::minecraft_dir::{LIBRARIES_DIR_NAME, VERSIONS_DIR_NAME};
+use crate::constants::network::MOJANG_LIBRARIES_URL;
+use crate::game_installer::download::file_sha1;
+use crate::game_installer::libraries::{collect_library_downloads, download_libraries_task};
+use crate::game_launcher::models::RuleContext;
+use crate::game_resolver::VersionManifest;
+use crate::game_resolver::models::Library;
+use crate::mod_loader::utils::{
+    check_game_version, extract_entry, library_path, path_string, read_entry, with_parent,
+    write_child_version,
+};
+use crate::scheduler::Scheduler;
+use crate::utils::abs_path_buf::AbsPathBuf;
+use crate::utils::executor::Executable;
+use anyhow::{Context, Result, anyhow};
+use log::info;
+use regex::{Captures, Regex};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::sync::LazyLock;
+use tempfile::TempDir;
+use thiserror::Error;
+use tokio::task::spawn_blocking;
+use zip::ZipArchive;
+
+const INSTALL_PROFILE_PATH: &str = "install_profile.json";
+const BUNDLED_MAVEN_DIR: &str = "maven";
+const MANIFEST_PATH: &str = "META-INF/MANIFEST.MF";
+const CLIENT_SIDE: &str = "client";
+
+#[derive(Debug, Error)]
+pub enum ForgeInstallError {
+    #[error("Processor {jar} failed with {status}:\n{output}")]
+    ProcessorFailed {
+        jar: String,
+        status: ExitStatus,
+        output: String,
+    },
+    #[error("Processor {jar} produced {path} with sha1 {actual}, expected {expected}")]
+    OutputMismatch {
+        jar: String,
+        path: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Unknown placeholder {{{0}}} in install profile")]
+    UnknownPlaceholder(String),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModernInstallProfile {
+    minecraft: String,
+    json: String,
+    #[serde(default)]
+    data: HashMap<String, SidedValue>,
+    #[serde(default)]
+    processors: Vec<Processor>,
+    #[serde(default)]
+    libraries: Vec<Library>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SidedValue {
+    client: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Processor {
+    // all sides when missing
+    sides: Option<Vec<String>>,
+    jar: String,
+    #[serde(default)]
+    classpath: Vec<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    outputs: HashMap<String, String>,
+}
+
+/// The 1.12 and older format, which ships a universal jar and the version json inline.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LegacyInstallProfile {
+    install: LegacyInstall,
+    version_info: Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LegacyInstall {
+    path: String,
+    file_path: String,
+    minecraft: String,
+}
+
+#[derive(Debug, Clone)]
+enum InstallProfile {
+    Modern {
+        profile: ModernInstallProfile,
+        version: Value,
+    },
+    Legacy(LegacyInstallProfile),
+}
+
+/// An installer jar of Forge or NeoForge, which share the same format.
+pub struct ForgeInstaller {
+    installer_path: PathBuf,
+    profile: InstallProfile,
+}
+
+impl ForgeInstaller {
+    pub async fn open(installer_path: PathBuf) -> Result<Self> {
+        let path = installer_path.clone();
+        let profile = spawn_blocking(move || read_install_profile(&path))
+            .await?
+            .with_context(|| format!("Invalid installer {}", installer_path.display()))?;
+        Ok(Self {
+            installer_path,
+            profile,
+        })
+    }
+
+    /// Installs the loader as a child of `vanilla`, running the install processors with `java`.
+    /// Libraries of the returned version still have to be downloaded.
+    pub async fn install(
+        &self,
+        scheduler: &Scheduler,
+        minecraft_dir: &AbsPathBuf,
+        vanilla: &VersionManifest,
+        java: &Path,
+    ) -> Result<VersionManifest> {
+        let minecraft = match &self.profile {
+            InstallProfile::Legacy(profile) => &profile.install.minecraft,
+            InstallProfile::Modern { profile, .. } => &profile.minecraft,
+        };
+        check_game_version("Forge installer", minecraft, vanilla)?;
+
+        let libraries_dir = minecraft_dir.join(LIBRARIES_DIR_NAME);
+        match &self.profile {
+            InstallProfile::Legacy(profile) => {
+                self.install_legacy(profile, minecraft_dir, &libraries_dir, vanilla)
+                    .await
+            }
+            InstallProfile::Modern { profile, version } => {
+                let dest = libraries_dir.clone();
+                self.with_installer(move |installer| extract_bundled_maven(installer, &dest))
+                    .await?;
+                download_profile_libraries(scheduler, &profile.libraries, &libraries_dir).await?;
+                self.run_processors(profile, minecraft_dir, &libraries_dir, vanilla, java)
+                    .await?;
+                write_child_version(minecraft_dir, with_parent(version.clone(), vanilla)).await
+            }
+        }
+    }
+
+    async fn install_legacy(
+        &self,
+        profile: &LegacyInstallProfile,
+        minecraft_dir: &AbsPathBuf,
+        libraries_dir: &AbsPathBuf,
+        vanilla: &VersionManifest,
+    ) -> Result<VersionManifest> {
+        let universal_jar = library_path(libraries_dir, &profile.install.path)?;
+        let entry = profile.install.file_path.clone();
+        self.with_installer(move |installer| extract_entry(installer, &entry, &universal_jar))
+            .await?;
+
+        let mut version = with_parent(profile.version_info.clone(), vanilla);
+        if let Some(libraries) = version["libraries"].as_array_mut() {
+            // server only libraries, and the ones forge only ships as `.pack.xz` with
+            // `checksums`, which the launcher cannot unpack
+            libraries.retain(|library| {
+                library.get("clientreq") != Some(&Value::Bool(false))
+                    && library.get("checksums").is_none()
+            });
+            // libraries without a repository come from mojang, as the old launcher assumed
+            for library in libraries {
+                if library.get("url").is_none() && library.get("downloads").is_none() {
+                    library["url"] = Value::String(MOJANG_LIBRARIES_URL.to_string());
+                }
+            }
+        }
+        write_child_version(minecraft_dir, version).await
+    }
+
+    async fn run_processors(
+        &self,
+        profile: &ModernInstallProfile,
+        minecraft_dir: &AbsPathBuf,
+        libraries_dir: &AbsPathBuf,
+        vanilla: &VersionManifest,
+        java: &Path,
+    ) -> Result<()> {
+        let vanilla_jar = minecraft_dir
+            .join(VERSIONS_DIR_NAME)
+            .join(&vanilla.id)
+            .join(format!("{}.jar", vanilla.id));
+        let mut data = HashMap::from([
+            ("SIDE".to_string(), CLIENT_SIDE.to_string()),
+            ("MINECRAFT_JAR".to_string(), path_string(&vanilla_jar)),
+            ("MINECRAFT_VERSION".to_string(), profile.minecraft.clone()),
+            ("ROOT".to_string(), path_string(minecraft_dir)),
+            ("INSTALLER".to_string(), path_string(&self.installer_path)),
+            ("LIBRARY_DIR".to_string(), path_string(libraries_dir)),
+        ]);
+
+        // files unpacked from the installer are only needed while the processors run
+        let data_dir = TempDir::new()?;
+        let data_root = data_dir.path().to_path_buf();
+        let profile_data = profile.data.clone();
+        let libraries = libraries_dir.clone();
+        let resolved = self
+            .with_installer(move |installer| {
+                profile_data
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let value =
+                            resolve_data_value(installer, &value.client, &data_root, &libraries)?;
+                        Ok((key, value))
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .await?;
+        data.extend(resolved);
+
+        for processor in &profile.processors {
+            if processor
+                .sides
+                .as_ref()
+                .is_some_and(|sides| !sides.iter().any(|side| side == CLIENT_SIDE))
+            {
+                continue;
+            }
+            run_processor(processor, &data, libraries_dir, java).await?;
+        }
+        Ok(())
+    }
+
+    async fn with_installer<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut ZipArchive<File>) -> Result<T> + Send + 'static,
+    {
+        let installer_path = self.installer_path.clone();
+        spawn_blocking(move || {
+            let mut installer = ZipArchive::new(File::open(&installer_path)?)?;
+            f(&mut installer)
+        })
+        .await?
+    }
+}
+
+fn read_install_profile(installer_path: &Path) -> Result<InstallProfile> {
+    let mut installer = ZipArchive::new(File::open(installer_path)?)?;
+    let profile: Value =
+        serde_json::from_slice(&read_entry(&mut installer, INSTALL_PROFILE_PATH)?)?;
+    if profile.get("install").is_some() && profile.get("versionInfo").is_some() {
+        return Ok(InstallProfile::Legacy(serde_json::from_value(profile)?));
+    }
+
+    let profile: ModernInstallProfile = serde_json::from_value(profile)?;
+    let version_path = profile.json.trim_start_matches('/');
+    let version = serde_json::from_slice(&read_entry(&mut installer, version_path)?)?;
+    Ok(InstallProfile::Modern { profile, version })
+}
+
+async fn download_profile_libraries(
+    scheduler: &Scheduler,
+    libraries: &[Library],
+    libraries_dir: &AbsPathBuf,
+) -> Result<()> {
+    let rule_context = RuleContext {
+        os_info: os_info::get(),
+        user_features: HashMap::new(),
+    };
+    let downloads = collect_library_downloads(libraries, libraries_dir, &rule_context);
+    scheduler
+        .run(download_libraries_task(Client::new(), downloads))
+        .await?;
+    Ok(())
+}
+
+async fn run_processor(
+    processor: &Processor,
+    data: &HashMap<String, String>,
+    libraries_dir: &AbsPathBuf,
+    java: &Path,
+) -> Result<()> {
+    let jar = library_path(libraries_dir, &processor.jar)?;
+    let main_class = {
+        let jar = jar.to_path_buf();
+        spawn_blocking(move || read_main_class(&jar)).await??
+    };
+    let mut classpath = vec![jar.to_path_buf()];
+    for coordinate in &processor.classpath {
+        classpath.push(library_path(libraries_dir, coordinate)?.to_path_buf());
+    }
+    let classpath = std::env::join_paths(classpath)?;
+
+    let mut args = vec![
+        "-cp".to_string(),
+        classpath.to_string_lossy().to_string(),
+        main_class,
+    ];
+    for arg in &processor.args {
+        args.push(substitute(arg, data, libraries_dir)?);
+    }
+    let outputs = processor
+        .outputs
+        .iter()
+        .map(|(path, sha1)| {
+            Ok((
+                substitute(path, data, libraries_dir)?,
+                substitute(sha1, data, libraries_dir)?,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    info!("Running forge processor {}", processor.jar);
+    let executable = Executable {
+        program: path_string(java),
+        args,
+        cwd: None,
+        kill_on_drop: true,
+    };
+    let (status, output) = executable.run_and_get_status().await?;
+    if !status.success() {
+        return Err(ForgeInstallError::ProcessorFailed {
+            jar: processor.jar.clone(),
+            status,
+            output,
+        }
+        .into());
+    }
+
+    for (path, expected) in outputs {
+        let actual = file_sha1(Path::new(&path)).await?;
+        if !actual.eq_ignore_ascii_case(&expected) {
+            return Err(ForgeInstallError::OutputMismatch {
+                jar: processor.jar.clone(),
+                path,
+                expected,
+                actual,
+            }
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Data values are `[artifact]` coordinates, `'literal'` strings or `/entry` files in the installer.
+fn resolve_data_value(
+    installer: &mut ZipArchive<File>,
+    value: &str,
+    data_dir: &Path,
+    libraries_dir: &AbsPathBuf,
+) -> Result<String> {
+    if let Some(coordinate) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        return Ok(path_string(&library_path(libraries_dir, coordinate)?));
+    }
+    if let Some(literal) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return Ok(literal.to_string());
+    }
+    if let Some(entry) = value.strip_prefix('/') {
+        let dest = data_dir.join(entry);
+        extract_entry(installer, entry, &dest)?;
+        return Ok(path_string(&dest));
+    }
+    Ok(value.to_string())
+}
+
+fn substitute(
+    arg: &str,
+    data: &HashMap<String, String>,
+    libraries_dir: &AbsPathBuf,
+) -> Result<String> {
+    static PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"\{(\w+)}").expect("Internal Error: Failed to compile placeholder regex")
+    });
+
+    if let Some(coordinate) = arg.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        return Ok(path_string(&library_path(libraries_dir, coordinate)?));
+    }
+    if let Some(unknown) = PLACEHOLDER
+        .captures_iter(arg)
+        .find(|caps| !data.contains_key(&caps[1]))
+    {
+        return Err(ForgeInstallError::UnknownPlaceholder(unknown[1].to_string()).into());
+    }
+    Ok(PLACEHOLDER
+        .replace_all(arg, |caps: &Captures| data[&caps[1]].clone())
+        .into_owned())
+}
+
+// modern installers bundle the loader's own artifacts under `maven/`
+fn extract_bundled_maven(
+    installer: &mut ZipArchive<File>,
+    libraries_dir: &AbsPathBuf,
+) -> Result<()> {
+    for i in 0..installer.len() {
+        let mut entry = installer.by_index(i)?;
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        let Ok(relative) = relative.strip_prefix(BUNDLED_MAVEN_DIR) else {
+            continue;
+        };
+        if entry.is_dir() {
+            continue;
+        }
+        let dest = libraries_dir.join(relative);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::io::copy(&mut entry, &mut File::create(&dest)?)?;
+    }
+    Ok(())
+}
+
+fn read_main_class(jar: &Path) -> Result<String> {
+    let mut archive = ZipArchive::new(File::open(jar)?)?;
+    let manifest = String::from_utf8(read_entry(&mut archive, MANIFEST_PATH)?)?;
+    manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("Main-Class:"))
+        .map(|main_class| main_class.trim().to_string())
+        .ok_or_else(|| anyhow!("No Main-Class in {}", jar.display()))
+}
@@ -0,0 +1,7 @@
+pub mod commands;
+pub mod fabric;
+pub mod forge;
+pub mod optifine;
+pub mod quilt;
+mod tests;
+pub mod utils;
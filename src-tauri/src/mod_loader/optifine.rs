@@ -0,0 +1,267 @@
+use crate::constants::minecraft_dir::{LIBRARIES_DIR_NAME, MODS_DIR_NAME, VERSIONS_DIR_NAME};
+use crate::constants::network::MOJANG_LIBRARIES_URL;
+use crate::game_resolver::VersionManifest;
+use crate::game_resolver::detection::{ModLoaderKind, detect_mod_loaders};
+use crate::mod_loader::utils::{
+    check_game_version, extract_entry, library_path, path_string, read_entry, write_child_version,
+};
+use crate::utils::abs_path_buf::AbsPathBuf;
+use crate::utils::executor::Executable;
+use anyhow::{Result, anyhow, bail};
+use log::info;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use specta::Type;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use tokio::fs;
+use tokio::task::spawn_blocking;
+use zip::ZipArchive;
+
+const TWEAKER_CLASS: &str = "optifine.OptiFineTweaker";
+const LAUNCH_WRAPPER_MAIN_CLASS: &str = "net.minecraft.launchwrapper.Launch";
+const MOJANG_LAUNCH_WRAPPER: &str = "net.minecraft:launchwrapper:1.12";
+const PATCHER_CLASS_PATH: &str = "optifine/Patcher.class";
+// newer installers bundle a launchwrapper fork that runs on java 9+
+const LAUNCH_WRAPPER_VERSION_PATH: &str = "launchwrapper-of.txt";
+const CONFIG_CLASS_PATHS: &[&str] = &["net/optifine/Config.class", "Config.class"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct OptiFineInfo {
+    pub minecraft_version: String,
+    // e.g. `HD_U_I7`
+    pub edition: String,
+}
+
+impl OptiFineInfo {
+    pub fn version_id(&self) -> String {
+        format!("{}-OptiFine_{}", self.minecraft_version, self.edition)
+    }
+
+    fn library_name(&self) -> String {
+        format!(
+            "optifine:OptiFine:{}_{}",
+            self.minecraft_version, self.edition
+        )
+    }
+
+    fn mod_file_name(&self) -> String {
+        format!("OptiFine_{}_{}.jar", self.minecraft_version, self.edition)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct OptiFineModInstall {
+    pub path: PathBuf,
+    // fabric and quilt only load OptiFine through the OptiFabric mod
+    pub requires_optifabric: bool,
+}
+
+/// A user supplied OptiFine installer jar, installed without any network access.
+pub struct OptiFineInstaller {
+    installer_path: PathBuf,
+    info: OptiFineInfo,
+    has_patcher: bool,
+    launch_wrapper_version: Option<String>,
+}
+
+impl OptiFineInstaller {
+    pub async fn open(installer_path: PathBuf) -> Result<Self> {
+        let path = installer_path.clone();
+        spawn_blocking(move || read_installer(path)).await?
+    }
+
+    pub fn info(&self) -> &OptiFineInfo {
+        &self.info
+    }
+
+    /// Installs OptiFine as a launchwrapper version inheriting from `vanilla`.
+    /// `java` runs the patcher of installers that ship one.
+    pub async fn install_standalone(
+        &self,
+        minecraft_dir: &AbsPathBuf,
+        vanilla: &VersionManifest,
+        java: &Path,
+    ) -> Result<VersionManifest> {
+        self.check_game_version(vanilla)?;
+        let libraries_dir = minecraft_dir.join(LIBRARIES_DIR_NAME);
+        let optifine_jar = library_path(&libraries_dir, &self.info.library_name())?;
+        if self.has_patcher {
+            let vanilla_jar = minecraft_dir
+                .join(VERSIONS_DIR_NAME)
+                .join(&vanilla.id)
+                .join(format!("{}.jar", vanilla.id));
+            self.patch(java, &vanilla_jar, &optifine_jar).await?;
+        } else {
+            fs::create_dir_all(optifine_jar.parent().unwrap_or(&libraries_dir)).await?;
+            fs::copy(&self.installer_path, &optifine_jar).await?;
+        }
+
+        let launch_wrapper = match &self.launch_wrapper_version {
+            Some(version) => {
+                let name = format!("optifine:launchwrapper-of:{version}");
+                let dest = library_path(&libraries_dir, &name)?;
+                let (installer, entry) = (
+                    self.installer_path.clone(),
+                    format!("launchwrapper-of-{version}.jar"),
+                );
+                spawn_blocking(move || {
+                    extract_entry(&mut ZipArchive::new(File::open(installer)?)?, &entry, &dest)
+                })
+                .await??;
+                json!({ "name": name })
+            }
+            None => json!({ "name": MOJANG_LAUNCH_WRAPPER, "url": MOJANG_LIBRARIES_URL }),
+        };
+
+        let mut version = json!({
+            "id": self.info.version_id(),
+            "inheritsFrom": vanilla.id,
+            "releaseTime": vanilla.release_time,
+            "time": vanilla.time,
+            "type": vanilla.version_type,
+            "mainClass": LAUNCH_WRAPPER_MAIN_CLASS,
+            "libraries": [{ "name": self.info.library_name() }, launch_wrapper],
+        });
+        // a child's minecraftArguments replace the parent's, while arguments are appended
+        match &vanilla.minecraft_arguments {
+            Some(arguments) => {
+                version["minecraftArguments"] =
+                    json!(format!("{arguments} --tweakClass {TWEAKER_CLASS}"));
+            }
+            None => {
+                version["arguments"] = json!({ "game": ["--tweakClass", TWEAKER_CLASS] });
+            }
+        }
+        write_child_version(minecraft_dir, version).await
+    }
+
+    /// Copies OptiFine into the mods folder of a Forge or Fabric instance.
+    pub async fn install_as_mod(
+        &self,
+        game_dir: &Path,
+        instance: &VersionManifest,
+    ) -> Result<OptiFineModInstall> {
+        self.check_game_version(instance)?;
+        let kinds: Vec<ModLoaderKind> = detect_mod_loaders(instance)
+            .into_iter()
+            .map(|loader| loader.kind)
+            .collect();
+        let requires_optifabric = kinds
+            .iter()
+            .any(|kind| matches!(kind, ModLoaderKind::Fabric | ModLoaderKind::Quilt));
+        if !requires_optifabric
+            && !kinds
+                .iter()
+                .any(|kind| matches!(kind, ModLoaderKind::Forge | ModLoaderKind::NeoForge))
+        {
+            bail!(
+                "{} has no mod loader, OptiFine can only be added to Forge or Fabric instances",
+                instance.id
+            );
+        }
+
+        let mods_dir = game_dir.join(MODS_DIR_NAME);
+        fs::create_dir_all(&mods_dir).await?;
+        let path = mods_dir.join(self.info.mod_file_name());
+        fs::copy(&self.installer_path, &path).await?;
+        info!("Copied OptiFine into {}", path.display());
+        Ok(OptiFineModInstall {
+            path,
+            requires_optifabric,
+        })
+    }
+
+    fn check_game_version(&self, version: &VersionManifest) -> Result<()> {
+        let name = format!("OptiFine {}", self.info.edition);
+        check_game_version(&name, &self.info.minecraft_version, version)
+    }
+
+    async fn patch(&self, java: &Path, vanilla_jar: &Path, optifine_jar: &Path) -> Result<()> {
+        if let Some(parent) = optifine_jar.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let installer = path_string(&self.installer_path);
+        let executable = Executable {
+            program: path_string(java),
+            args: vec![
+                "-cp".to_string(),
+                installer.clone(),
+                "optifine.Patcher".to_string(),
+                path_string(vanilla_jar),
+                installer,
+                path_string(optifine_jar),
+            ],
+            cwd: None,
+            kill_on_drop: true,
+        };
+        let (status, output) = executable.run_and_get_status().await?;
+        if !status.success() || !fs::try_exists(optifine_jar).await? {
+            return Err(anyhow!("OptiFine patcher failed with {status}:\n{output}"));
+        }
+        Ok(())
+    }
+}
+
+fn read_installer(installer_path: PathBuf) -> Result<OptiFineInstaller> {
+    let mut installer = ZipArchive::new(File::open(&installer_path)?)?;
+    let info = CONFIG_CLASS_PATHS
+        .iter()
+        .find_map(|path| read_entry(&mut installer, path).ok())
+        .and_then(|class| parse_optifine_info(&String::from_utf8_lossy(&class)))
+        .or_else(|| parse_optifine_info(&installer_path.file_name()?.to_string_lossy()))
+        .ok_or_else(|| anyhow!("{} is not an OptiFine installer", installer_path.display()))?;
+    let has_patcher = installer.by_name(PATCHER_CLASS_PATH).is_ok();
+    let launch_wrapper_version = read_entry(&mut installer, LAUNCH_WRAPPER_VERSION_PATH)
+        .ok()
+        .map(|version| String::from_utf8_lossy(&version).trim().to_string())
+        .filter(|version| !version.is_empty());
+    Ok(OptiFineInstaller {
+        installer_path,
+        info,
+        has_patcher,
+        launch_wrapper_version,
+    })
+}
+
+// the version constant in Config.class, or the installer's default file name
+fn parse_optifine_info(text: &str) -> Option<OptiFineInfo> {
+    static RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"OptiFine_(\d+(?:\.\d+)+)_(HD_\w+)")
+            .expect("Internal Error: Failed to compile OptiFine version regex")
+    });
+    let caps = RE.captures(text)?;
+    Some(OptiFineInfo {
+        minecraft_version: caps[1].to_string(),
+        edition: caps[2].to_string(),
+    })
+}
+
+#[cfg(test)]
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_optifine_info() {
+        let info = |text| parse_optifine_info(text).map(|info| info.version_id());
+
+        assert_eq!(
+            info("\u{1}\u{0}\u{17}OptiFine_1.20.4_HD_U_I7\u{1}"),
+            Some("1.20.4-OptiFine_HD_U_I7".to_string())
+        );
+        assert_eq!(
+            info("OptiFine_1.7.10_HD_U_E7.jar"),
+            Some("1.7.10-OptiFine_HD_U_E7".to_string())
+        );
+        assert_eq!(
+            info("preview_OptiFine_1.21.1_HD_U_J1_pre9.jar"),
+            Some("1.21.1-OptiFine_HD_U_J1_pre9".to_string())
+        );
+        assert_eq!(info("forge-1.20.4-installer.jar"), None);
+    }
+}
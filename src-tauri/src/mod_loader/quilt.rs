@@ -0,0 +1,69 @@
+use crate::game_resolver::VersionManifest;
+use crate::mod_loader::fabric::FabricMetaClient;
+use crate::mod_loader::utils::write_child_version;
+use crate::utils::abs_path_buf::AbsPathBuf;
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct QuiltLoaderVersion {
+    pub version: String,
+    pub maven: String,
+    // only shown to the user, the loader profile already lists whichever of hashed and
+    // intermediary the loader remaps with, so both end up as ordinary libraries
+    pub hashed: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MavenEntry {
+    maven: String,
+    version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LoaderEntry {
+    loader: MavenEntry,
+    hashed: Option<MavenEntry>,
+}
+
+// quilt meta is the fabric protocol under v3, only the loader entries carry more
+pub struct QuiltMetaClient {
+    meta: FabricMetaClient,
+}
+
+impl QuiltMetaClient {
+    pub fn new(client: Client, base_url: impl Into<String>) -> Self {
+        Self {
+            meta: FabricMetaClient::with_api_version(client, base_url, "v3"),
+        }
+    }
+
+    // quilt marks betas in the version instead of a stable flag
+    pub async fn list_loader_versions(
+        &self,
+        vanilla: &VersionManifest,
+    ) -> Result<Vec<QuiltLoaderVersion>> {
+        let entries: Vec<LoaderEntry> = self.meta.fetch_loader_entries(vanilla).await?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| QuiltLoaderVersion {
+                version: entry.loader.version,
+                maven: entry.loader.maven,
+                hashed: entry.hashed.map(|hashed| hashed.maven),
+            })
+            .collect())
+    }
+
+    pub async fn install(
+        &self,
+        minecraft_dir: &AbsPathBuf,
+        vanilla: &VersionManifest,
+        loader_version: &str,
+    ) -> Result<VersionManifest> {
+        let profile = self.meta.fetch_profile(vanilla, loader_version).await?;
+        write_child_version(minecraft_dir, profile).await
+    }
+}
@@ -0,0 +1,737 @@
+#![cfg_attr(coverage_nightly, coverage(off))]
+#![cfg(test)]
+
+use crate::constants::minecraft_dir::{LIBRARIES_DIR_NAME, VERSIONS_DIR_NAME};
+use crate::game_installer::download::file_sha1;
+use crate::game_installer::libraries::{collect_library_downloads, download_libraries_task};
+use crate::game_launcher::models::RuleContext;
+use crate::game_resolver::VersionManifest;
+use crate::game_resolver::resolve::resolve_installed_version;
+use crate::mod_loader::fabric::FabricMetaClient;
+use crate::mod_loader::forge::{ForgeInstallError, ForgeInstaller};
+use crate::mod_loader::optifine::OptiFineInstaller;
+use crate::mod_loader::quilt::QuiltMetaClient;
+use crate::scheduler::Scheduler;
+use crate::utils::abs_path_buf::AbsPathBuf;
+use crate::utils::os_info::mock_info;
+use crate::utils::test_server::TestServer;
+use os_info::Type;
+use reqwest::Client;
+use serde_json::{Value, json};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+const FABRIC_LOADER_JAR: &[u8] = b"fabric loader jar";
+const QUILT_LOADER_JAR: &[u8] = b"quilt loader jar";
+const HASHED_JAR: &[u8] = b"hashed mappings jar";
+const INTERMEDIARY_JAR: &[u8] = b"intermediary mappings jar";
+const JOPT_JAR: &[u8] = b"jopt simple jar";
+const PATCHED_CLIENT: &[u8] = b"patched client";
+const FORGE_CLIENT_PATH: &str =
+    "net/minecraftforge/forge/1.20.4-49.0.30/forge-1.20.4-49.0.30-client.jar";
+
+fn vanilla() -> VersionManifest {
+    serde_json::from_value(json!({
+        "id": "1.20.4",
+        "mainClass": "net.minecraft.client.main.Main",
+        "releaseTime": "2023-12-07T12:56:20+00:00",
+        "time": "2023-12-07T12:56:20+00:00",
+        "type": "release",
+        "arguments": { "game": [], "jvm": [] },
+        "assetIndex": { "id": "12", "sha1": "", "size": 0, "totalSize": 0, "url": "" }
+    }))
+    .unwrap()
+}
+
+fn write_vanilla(minecraft_dir: &AbsPathBuf) {
+    let version_dir = minecraft_dir.join(VERSIONS_DIR_NAME).join("1.20.4");
+    std::fs::create_dir_all(&version_dir).unwrap();
+    let json = serde_json::to_vec(&vanilla()).unwrap();
+    std::fs::write(version_dir.join("1.20.4.json"), json).unwrap();
+    std::fs::write(version_dir.join("1.20.4.jar"), b"").unwrap();
+}
+
+async fn download_libraries(minecraft_dir: &AbsPathBuf, manifest: &VersionManifest) {
+    let rule_context = RuleContext {
+        os_info: mock_info(Type::Linux, "1.0", "x86_64"),
+        user_features: HashMap::new(),
+    };
+    let libraries_dir = minecraft_dir.join(LIBRARIES_DIR_NAME);
+    let downloads = collect_library_downloads(&manifest.libraries, &libraries_dir, &rule_context);
+    Scheduler::new(4)
+        .run(download_libraries_task(Client::new(), downloads))
+        .await
+        .unwrap();
+}
+
+fn read_library(minecraft_dir: &AbsPathBuf, path: &str) -> Vec<u8> {
+    std::fs::read(minecraft_dir.join(LIBRARIES_DIR_NAME).join(path)).unwrap()
+}
+
+fn profile(id: &str, main_class: &str, libraries: Value) -> Vec<u8> {
+    json!({
+        "id": id,
+        "inheritsFrom": "1.20.4",
+        "releaseTime": "2024-11-29T10:36:44+0000",
+        "time": "2024-11-29T10:36:44+0000",
+        "type": "release",
+        "mainClass": main_class,
+        "arguments": { "game": [], "jvm": [] },
+        "libraries": libraries
+    })
+    .to_string()
+    .into_bytes()
+}
+
+// the profiles point their libraries to the maven server
+async fn start_servers() -> (TestServer, TestServer) {
+    let maven = TestServer::start(HashMap::from([
+        (
+            "/net/fabricmc/fabric-loader/0.16.9/fabric-loader-0.16.9.jar".to_string(),
+            FABRIC_LOADER_JAR.to_vec(),
+        ),
+        (
+            "/net/fabricmc/intermediary/1.20.4/intermediary-1.20.4.jar".to_string(),
+            INTERMEDIARY_JAR.to_vec(),
+        ),
+        (
+            "/quilt/org/quiltmc/quilt-loader/0.27.1/quilt-loader-0.27.1.jar".to_string(),
+            QUILT_LOADER_JAR.to_vec(),
+        ),
+        (
+            "/quilt/org/quiltmc/hashed/1.20.4/hashed-1.20.4.jar".to_string(),
+            HASHED_JAR.to_vec(),
+        ),
+    ]))
+    .await;
+    let fabric_maven = format!("{}/", maven.base_url);
+    let quilt_maven = format!("{}/quilt/", maven.base_url);
+
+    let fabric_loaders = json!([
+        { "loader": { "separator": ".", "build": 9, "maven": "net.fabricmc:fabric-loader:0.16.9", "version": "0.16.9", "stable": true } },
+        { "loader": { "separator": ".", "build": 8, "maven": "net.fabricmc:fabric-loader:0.16.8", "version": "0.16.8", "stable": false } }
+    ]);
+    let fabric_profile = profile(
+        "fabric-loader-0.16.9-1.20.4",
+        "net.fabricmc.loader.impl.launch.knot.KnotClient",
+        json!([
+            { "name": "net.fabricmc:intermediary:1.20.4", "url": fabric_maven },
+            { "name": "net.fabricmc:fabric-loader:0.16.9", "url": fabric_maven }
+        ]),
+    );
+    let quilt_loaders = json!([
+        {
+            "loader": { "separator": ".", "build": 1, "maven": "org.quiltmc:quilt-loader:0.27.1", "version": "0.27.1" },
+            "hashed": { "maven": "org.quiltmc:hashed:1.20.4", "version": "1.20.4" },
+            "intermediary": { "maven": "net.fabricmc:intermediary:1.20.4", "version": "1.20.4" }
+        },
+        {
+            "loader": { "separator": ".", "build": 1, "maven": "org.quiltmc:quilt-loader:0.27.0-beta.1", "version": "0.27.0-beta.1" },
+            "intermediary": { "maven": "net.fabricmc:intermediary:1.20.4", "version": "1.20.4" }
+        }
+    ]);
+    let quilt_profile = profile(
+        "quilt-loader-0.27.1-1.20.4",
+        "org.quiltmc.loader.impl.launch.knot.KnotClient",
+        json!([
+            { "name": "org.quiltmc:hashed:1.20.4", "url": quilt_maven },
+            { "name": "net.fabricmc:intermediary:1.20.4", "url": fabric_maven },
+            { "name": "org.quiltmc:quilt-loader:0.27.1", "url": quilt_maven }
+        ]),
+    );
+
+    let meta = TestServer::start(HashMap::from([
+        (
+            "/v2/versions/loader/1.20.4".to_string(),
+            fabric_loaders.to_string().into_bytes(),
+        ),
+        (
+            "/v2/versions/loader/1.20.4/0.16.9/profile/json".to_string(),
+            fabric_profile,
+        ),
+        (
+            "/v3/versions/loader/1.20.4".to_string(),
+            quilt_loaders.to_string().into_bytes(),
+        ),
+        (
+            "/v3/versions/loader/1.20.4/0.27.1/profile/json".to_string(),
+            quilt_profile,
+        ),
+    ]))
+    .await;
+    (meta, maven)
+}
+
+#[tokio::test]
+async fn test_lists_fabric_loaders_for_the_vanilla_version() {
+    let (server, _maven) = start_servers().await;
+    let meta = FabricMetaClient::new(Client::new(), &server.base_url);
+
+    let versions = meta.list_loader_versions(&vanilla()).await.unwrap();
+
+    assert_eq!(
+        versions
+            .iter()
+            .map(|v| v.version.as_str())
+            .collect::<Vec<_>>(),
+        ["0.16.9", "0.16.8"]
+    );
+    assert!(versions[0].stable);
+}
+
+#[tokio::test]
+async fn test_installs_fabric_as_a_child_version() {
+    let (server, _maven) = start_servers().await;
+    let meta = FabricMetaClient::new(Client::new(), &server.base_url);
+    let root = TempDir::new().unwrap();
+    let minecraft_dir = AbsPathBuf::new(root.path()).unwrap();
+    write_vanilla(&minecraft_dir);
+
+    let manifest = meta
+        .install(&minecraft_dir, &vanilla(), "0.16.9")
+        .await
+        .unwrap();
+    download_libraries(&minecraft_dir, &manifest).await;
+
+    let resolved = resolve_installed_version(minecraft_dir.clone(), "fabric-loader-0.16.9-1.20.4")
+        .await
+        .unwrap();
+    assert_eq!(
+        resolved.main_class,
+        "net.fabricmc.loader.impl.launch.knot.KnotClient"
+    );
+    assert!(resolved.asset_index.is_some(), "inherited from vanilla");
+    assert_eq!(
+        read_library(
+            &minecraft_dir,
+            "net/fabricmc/fabric-loader/0.16.9/fabric-loader-0.16.9.jar"
+        ),
+        FABRIC_LOADER_JAR
+    );
+}
+
+#[tokio::test]
+async fn test_fetches_fabric_profile_by_game_version_of_a_renamed_vanilla() {
+    let (server, _maven) = start_servers().await;
+    let meta = FabricMetaClient::new(Client::new(), &server.base_url);
+    let mut renamed = vanilla();
+    renamed.id = "my-1.20.4".to_string();
+    renamed.client_version = Some("1.20.4".to_string());
+
+    let profile = meta.fetch_profile(&renamed, "0.16.9").await.unwrap();
+
+    assert_eq!(profile["inheritsFrom"], "my-1.20.4");
+    assert_eq!(profile["id"], "fabric-loader-0.16.9-1.20.4");
+}
+
+#[tokio::test]
+async fn test_lists_quilt_loaders_with_hashed_mappings() {
+    let (server, _maven) = start_servers().await;
+    let meta = QuiltMetaClient::new(Client::new(), &server.base_url);
+
+    let versions = meta.list_loader_versions(&vanilla()).await.unwrap();
+
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0].maven, "org.quiltmc:quilt-loader:0.27.1");
+    assert_eq!(
+        versions[0].hashed.as_deref(),
+        Some("org.quiltmc:hashed:1.20.4")
+    );
+    assert_eq!(versions[1].hashed, None);
+}
+
+#[tokio::test]
+async fn test_installs_quilt_with_its_own_mappings() {
+    let (server, _maven) = start_servers().await;
+    let meta = QuiltMetaClient::new(Client::new(), &server.base_url);
+    let root = TempDir::new().unwrap();
+    let minecraft_dir = AbsPathBuf::new(root.path()).unwrap();
+    write_vanilla(&minecraft_dir);
+
+    let manifest = meta
+        .install(&minecraft_dir, &vanilla(), "0.27.1")
+        .await
+        .unwrap();
+    download_libraries(&minecraft_dir, &manifest).await;
+
+    let resolved = resolve_installed_version(minecraft_dir.clone(), "quilt-loader-0.27.1-1.20.4")
+        .await
+        .unwrap();
+    assert_eq!(
+        resolved.main_class,
+        "org.quiltmc.loader.impl.launch.knot.KnotClient"
+    );
+    assert_eq!(
+        read_library(
+            &minecraft_dir,
+            "org/quiltmc/hashed/1.20.4/hashed-1.20.4.jar"
+        ),
+        HASHED_JAR
+    );
+    assert_eq!(
+        read_library(
+            &minecraft_dir,
+            "net/fabricmc/intermediary/1.20.4/intermediary-1.20.4.jar"
+        ),
+        INTERMEDIARY_JAR
+    );
+    assert_eq!(
+        read_library(
+            &minecraft_dir,
+            "org/quiltmc/quilt-loader/0.27.1/quilt-loader-0.27.1.jar"
+        ),
+        QUILT_LOADER_JAR
+    );
+}
+
+fn zip_bytes(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = ZipWriter::new(std::io::Cursor::new(vec![]));
+    for (name, content) in entries {
+        writer
+            .start_file(*name, SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(content).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+fn write_installer(dir: &Path, entries: &[(&str, &[u8])]) -> PathBuf {
+    let path = dir.join("installer.jar");
+    std::fs::write(&path, zip_bytes(entries)).unwrap();
+    path
+}
+
+// stands in for java: records its arguments, then runs `script`
+#[cfg(unix)]
+fn fake_java(dir: &Path, script: &str) -> PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.join("java");
+    let script = format!("#!/bin/sh\necho \"$@\" > \"$(dirname \"$0\")/args.txt\"\n{script}");
+    std::fs::write(&path, script).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+// writes the file after `--output`, like the forge binary patcher
+const FORGE_PROCESSOR_SCRIPT: &str = r#"while [ $# -gt 0 ]; do
+  if [ "$1" = "--output" ]; then
+    mkdir -p "$(dirname "$2")"
+    printf 'patched client' > "$2"
+  fi
+  shift
+done
+"#;
+
+fn modern_installer(dir: &Path, jopt_url: &str, patched_sha1: &str) -> PathBuf {
+    let tool_jar = zip_bytes(&[(
+        "META-INF/MANIFEST.MF",
+        b"Manifest-Version: 1.0\r\nMain-Class: net.minecraftforge.installertools.ConsoleTool\r\n",
+    )]);
+    let install_profile = json!({
+        "spec": 1,
+        "profile": "forge",
+        "version": "1.20.4-forge-49.0.30",
+        "minecraft": "1.20.4",
+        "json": "/version.json",
+        "data": {
+            "MAPPINGS": { "client": "/data/client.txt", "server": "/data/server.txt" },
+            "PATCHED": {
+                "client": "[net.minecraftforge:forge:1.20.4-49.0.30:client]",
+                "server": "[net.minecraftforge:forge:1.20.4-49.0.30:server]"
+            },
+            "PATCHED_SHA": { "client": format!("'{patched_sha1}'"), "server": "''" }
+        },
+        "processors": [
+            { "sides": ["server"], "jar": "net.minecraftforge:installertools:1.3.0", "args": ["--task", "SERVER_ONLY"] },
+            {
+                "jar": "net.minecraftforge:installertools:1.3.0",
+                "classpath": ["net.sf.jopt-simple:jopt-simple:5.0.4"],
+                "args": ["--side", "{SIDE}", "--mappings", "{MAPPINGS}", "--input", "{MINECRAFT_JAR}", "--output", "{PATCHED}"],
+                "outputs": { "{PATCHED}": "{PATCHED_SHA}" }
+            }
+        ],
+        "libraries": [
+            {
+                "name": "net.minecraftforge:installertools:1.3.0",
+                "downloads": { "artifact": {
+                    "path": "net/minecraftforge/installertools/1.3.0/installertools-1.3.0.jar",
+                    "url": "", "sha1": "", "size": 0
+                } }
+            },
+            {
+                "name": "net.sf.jopt-simple:jopt-simple:5.0.4",
+                "downloads": { "artifact": {
+                    "path": "net/sf/jopt-simple/jopt-simple/5.0.4/jopt-simple-5.0.4.jar",
+                    "url": jopt_url, "sha1": hex::encode(Sha1::digest(JOPT_JAR)), "size": JOPT_JAR.len()
+                } }
+            }
+        ]
+    });
+    let version = json!({
+        "id": "1.20.4-forge-49.0.30",
+        "inheritsFrom": "1.20.4",
+        "releaseTime": "2024-01-01T00:00:00+00:00",
+        "time": "2024-01-01T00:00:00+00:00",
+        "type": "release",
+        "mainClass": "cpw.mods.bootstraplauncher.BootstrapLauncher",
+        "arguments": { "game": ["--launchTarget", "forgeclient"], "jvm": [] },
+        "libraries": [
+            {
+                "name": "net.minecraftforge:forge:1.20.4-49.0.30:client",
+                "downloads": { "artifact": { "path": FORGE_CLIENT_PATH, "url": "", "sha1": "", "size": 0 } }
+            }
+        ]
+    });
+    write_installer(
+        dir,
+        &[
+            (
+                "install_profile.json",
+                install_profile.to_string().as_bytes(),
+            ),
+            ("version.json", version.to_string().as_bytes()),
+            ("data/client.txt", b"client mappings"),
+            ("data/server.txt", b"server mappings"),
+            (
+                "maven/net/minecraftforge/installertools/1.3.0/installertools-1.3.0.jar",
+                &tool_jar,
+            ),
+        ],
+    )
+}
+
+async fn start_jopt_server() -> (TestServer, String) {
+    let server = TestServer::start(HashMap::from([(
+        "/jopt-simple-5.0.4.jar".to_string(),
+        JOPT_JAR.to_vec(),
+    )]))
+    .await;
+    let url = format!("{}/jopt-simple-5.0.4.jar", server.base_url);
+    (server, url)
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_installs_modern_forge_by_running_client_processors() {
+    let (_server, jopt_url) = start_jopt_server().await;
+    let root = TempDir::new().unwrap();
+    let minecraft_dir = AbsPathBuf::new(&root.path().join(".minecraft")).unwrap();
+    write_vanilla(&minecraft_dir);
+    let java = fake_java(root.path(), FORGE_PROCESSOR_SCRIPT);
+    let patched_sha1 = hex::encode(Sha1::digest(PATCHED_CLIENT));
+    let installer = modern_installer(root.path(), &jopt_url, &patched_sha1);
+
+    let manifest = ForgeInstaller::open(installer)
+        .await
+        .unwrap()
+        .install(&Scheduler::new(4), &minecraft_dir, &vanilla(), &java)
+        .await
+        .unwrap();
+    download_libraries(&minecraft_dir, &manifest).await;
+
+    let args = std::fs::read_to_string(root.path().join("args.txt")).unwrap();
+    assert!(args.contains("net.minecraftforge.installertools.ConsoleTool --side client"));
+    assert!(args.contains("jopt-simple-5.0.4.jar"));
+    assert!(args.contains("versions/1.20.4/1.20.4.jar"));
+    assert!(!args.contains("SERVER_ONLY"));
+    assert_eq!(
+        file_sha1(
+            &minecraft_dir
+                .join(LIBRARIES_DIR_NAME)
+                .join(FORGE_CLIENT_PATH)
+        )
+        .await
+        .unwrap(),
+        patched_sha1
+    );
+    let resolved = resolve_installed_version(minecraft_dir.clone(), "1.20.4-forge-49.0.30")
+        .await
+        .unwrap();
+    assert_eq!(
+        resolved.main_class,
+        "cpw.mods.bootstraplauncher.BootstrapLauncher"
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_rejects_processor_outputs_with_wrong_hash() {
+    let (_server, jopt_url) = start_jopt_server().await;
+    let root = TempDir::new().unwrap();
+    let minecraft_dir = AbsPathBuf::new(&root.path().join(".minecraft")).unwrap();
+    write_vanilla(&minecraft_dir);
+    let java = fake_java(root.path(), FORGE_PROCESSOR_SCRIPT);
+    let installer = modern_installer(root.path(), &jopt_url, &"0".repeat(40));
+
+    let err = ForgeInstaller::open(installer)
+        .await
+        .unwrap()
+        .install(&Scheduler::new(4), &minecraft_dir, &vanilla(), &java)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        err.downcast_ref::<ForgeInstallError>(),
+        Some(ForgeInstallError::OutputMismatch { .. })
+    ));
+    let version_dir = minecraft_dir
+        .join(VERSIONS_DIR_NAME)
+        .join("1.20.4-forge-49.0.30");
+    assert!(
+        !version_dir.exists(),
+        "a failed install must not register the version"
+    );
+}
+
+#[tokio::test]
+async fn test_installs_legacy_forge_from_the_universal_jar() {
+    let root = TempDir::new().unwrap();
+    let minecraft_dir = AbsPathBuf::new(&root.path().join(".minecraft")).unwrap();
+    let install_profile = json!({
+        "install": {
+            "profileName": "Forge",
+            "target": "1.12.2-forge1.12.2-14.23.5.2859",
+            "path": "net.minecraftforge:forge:1.12.2-14.23.5.2859",
+            "version": "forge 1.12.2-14.23.5.2859",
+            "filePath": "forge-1.12.2-14.23.5.2859-universal.jar",
+            "minecraft": "1.12.2"
+        },
+        "versionInfo": {
+            "id": "1.12.2-forge1.12.2-14.23.5.2859",
+            "time": "2019-08-25T17:04:09+0000",
+            "releaseTime": "1960-01-01T00:00:00-0700",
+            "type": "release",
+            "minecraftArguments": "--tweakClass net.minecraftforge.fml.common.launcher.FMLTweaker",
+            "mainClass": "net.minecraft.launchwrapper.Launch",
+            "inheritsFrom": "1.12.2",
+            "jar": "1.12.2",
+            "libraries": [
+                { "name": "net.minecraftforge:forge:1.12.2-14.23.5.2859", "url": "https://maven.minecraftforge.net/" },
+                { "name": "net.minecraft:launchwrapper:1.12", "serverreq": true },
+                { "name": "org.scala-lang:scala-library:2.11.1", "url": "https://maven.minecraftforge.net/", "checksums": ["0a1b2c"], "serverreq": true, "clientreq": true },
+                { "name": "net.sf.jopt-simple:jopt-simple:5.0.3", "serverreq": true, "clientreq": false }
+            ]
+        }
+    });
+    let installer = write_installer(
+        root.path(),
+        &[
+            (
+                "install_profile.json",
+                install_profile.to_string().as_bytes(),
+            ),
+            ("forge-1.12.2-14.23.5.2859-universal.jar", b"universal jar"),
+        ],
+    );
+
+    let installer = ForgeInstaller::open(installer).await.unwrap();
+    let mut vanilla_1_12_2 = vanilla();
+    vanilla_1_12_2.id = "1.12.2".to_string();
+
+    let mismatch = installer
+        .install(
+            &Scheduler::new(4),
+            &minecraft_dir,
+            &vanilla(),
+            Path::new("java"),
+        )
+        .await;
+    let manifest = installer
+        .install(
+            &Scheduler::new(4),
+            &minecraft_dir,
+            &vanilla_1_12_2,
+            Path::new("java"),
+        )
+        .await
+        .unwrap();
+
+    assert!(
+        mismatch
+            .unwrap_err()
+            .to_string()
+            .contains("minecraft 1.12.2")
+    );
+    assert_eq!(manifest.inherits_from.as_deref(), Some("1.12.2"));
+    assert_eq!(
+        manifest
+            .libraries
+            .iter()
+            .map(|library| library.name.as_str())
+            .collect::<Vec<_>>(),
+        [
+            "net.minecraftforge:forge:1.12.2-14.23.5.2859",
+            "net.minecraft:launchwrapper:1.12"
+        ],
+        "server only and pack.xz libraries are dropped"
+    );
+    assert_eq!(
+        manifest.libraries[1].url.as_deref(),
+        Some("https://libraries.minecraft.net/")
+    );
+    assert_eq!(
+        read_library(
+            &minecraft_dir,
+            "net/minecraftforge/forge/1.12.2-14.23.5.2859/forge-1.12.2-14.23.5.2859.jar"
+        ),
+        b"universal jar"
+    );
+}
+
+fn optifine_installer(dir: &Path, extra_entries: &[(&str, &[u8])]) -> PathBuf {
+    let mut entries: Vec<(&str, &[u8])> = vec![
+        (
+            "net/optifine/Config.class",
+            b"\xca\xfe\x01\x00\x17OptiFine_1.20.4_HD_U_I7\x01",
+        ),
+        ("optifine/Installer.class", b""),
+    ];
+    entries.extend_from_slice(extra_entries);
+    let path = dir.join("OptiFine.jar");
+    std::fs::write(&path, zip_bytes(&entries)).unwrap();
+    path
+}
+
+#[tokio::test]
+async fn test_installs_optifine_as_a_launchwrapper_version() {
+    let root = TempDir::new().unwrap();
+    let minecraft_dir = AbsPathBuf::new(&root.path().join(".minecraft")).unwrap();
+    write_vanilla(&minecraft_dir);
+    let installer = optifine_installer(
+        root.path(),
+        &[
+            ("launchwrapper-of.txt", b"2.3\n"),
+            ("launchwrapper-of-2.3.jar", b"launchwrapper fork"),
+        ],
+    );
+
+    let manifest = OptiFineInstaller::open(installer.clone())
+        .await
+        .unwrap()
+        .install_standalone(&minecraft_dir, &vanilla(), Path::new("java"))
+        .await
+        .unwrap();
+
+    assert_eq!(manifest.id, "1.20.4-OptiFine_HD_U_I7");
+    let resolved = resolve_installed_version(minecraft_dir.clone(), &manifest.id)
+        .await
+        .unwrap();
+    assert_eq!(resolved.main_class, "net.minecraft.launchwrapper.Launch");
+    assert_eq!(
+        serde_json::to_value(&resolved.arguments.unwrap().game).unwrap(),
+        json!(["--tweakClass", "optifine.OptiFineTweaker"])
+    );
+    assert_eq!(
+        read_library(
+            &minecraft_dir,
+            "optifine/OptiFine/1.20.4_HD_U_I7/OptiFine-1.20.4_HD_U_I7.jar"
+        ),
+        std::fs::read(installer).unwrap()
+    );
+    assert_eq!(
+        read_library(
+            &minecraft_dir,
+            "optifine/launchwrapper-of/2.3/launchwrapper-of-2.3.jar"
+        ),
+        b"launchwrapper fork"
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_runs_the_optifine_patcher_against_the_vanilla_jar() {
+    let root = TempDir::new().unwrap();
+    let minecraft_dir = AbsPathBuf::new(&root.path().join(".minecraft")).unwrap();
+    write_vanilla(&minecraft_dir);
+    let installer = optifine_installer(root.path(), &[("optifine/Patcher.class", b"")]);
+    let java = fake_java(
+        root.path(),
+        "for last; do :; done\nprintf 'patched optifine' > \"$last\"\n",
+    );
+
+    let manifest = OptiFineInstaller::open(installer)
+        .await
+        .unwrap()
+        .install_standalone(&minecraft_dir, &vanilla(), &java)
+        .await
+        .unwrap();
+
+    let args = std::fs::read_to_string(root.path().join("args.txt")).unwrap();
+    assert!(args.contains("optifine.Patcher"));
+    assert!(args.contains("versions/1.20.4/1.20.4.jar"));
+    assert_eq!(
+        read_library(
+            &minecraft_dir,
+            "optifine/OptiFine/1.20.4_HD_U_I7/OptiFine-1.20.4_HD_U_I7.jar"
+        ),
+        b"patched optifine"
+    );
+    assert_eq!(
+        manifest.libraries[1].url.as_deref(),
+        Some("https://libraries.minecraft.net/")
+    );
+}
+
+#[tokio::test]
+async fn test_copies_optifine_into_the_mods_folder() {
+    let root = TempDir::new().unwrap();
+    let installer = OptiFineInstaller::open(optifine_installer(root.path(), &[]))
+        .await
+        .unwrap();
+    let mut fabric = vanilla();
+    fabric.libraries = serde_json::from_value(json!([
+        { "name": "net.fabricmc:fabric-loader:0.16.9", "url": "https://maven.fabricmc.net/" }
+    ]))
+    .unwrap();
+
+    let installed = installer
+        .install_as_mod(root.path(), &fabric)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        installed.path,
+        root.path().join("mods/OptiFine_1.20.4_HD_U_I7.jar")
+    );
+    assert!(installed.path.is_file());
+    assert!(installed.requires_optifabric);
+    assert!(
+        installer
+            .install_as_mod(root.path(), &vanilla())
+            .await
+            .is_err(),
+        "vanilla has no mod loader"
+    );
+}
+
+#[tokio::test]
+async fn test_rejects_optifine_for_another_game_version() {
+    let root = TempDir::new().unwrap();
+    let minecraft_dir = AbsPathBuf::new(&root.path().join(".minecraft")).unwrap();
+    let installer = OptiFineInstaller::open(optifine_installer(root.path(), &[]))
+        .await
+        .unwrap();
+    let mut forge_1_12_2 = vanilla();
+    forge_1_12_2.id = "1.12.2-forge-14.23.5.2859".to_string();
+    forge_1_12_2.libraries = serde_json::from_value(json!([
+        { "name": "net.minecraftforge:forge:1.12.2-14.23.5.2859" }
+    ]))
+    .unwrap();
+    let mut vanilla_1_12_2 = vanilla();
+    vanilla_1_12_2.id = "1.12.2".to_string();
+
+    let as_mod = installer.install_as_mod(root.path(), &forge_1_12_2).await;
+    let standalone = installer
+        .install_standalone(&minecraft_dir, &vanilla_1_12_2, Path::new("java"))
+        .await;
+
+    assert!(as_mod.unwrap_err().to_string().contains("minecraft 1.20.4"));
+    assert!(standalone.is_err());
+    assert!(!root.path().join("mods").exists());
+}
@@ -0,0 +1,85 @@
+use crate::constants::minecraft_dir::VERSIONS_DIR_NAME;
+use crate::game_resolver::VersionManifest;
+use crate::game_resolver::detection::detect_game_version;
+use crate::game_resolver::models::Library;
+use crate::utils::abs_path_buf::AbsPathBuf;
+use anyhow::{Context, Result, anyhow, bail};
+use log::info;
+use serde_json::Value;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use tokio::fs;
+use zip::ZipArchive;
+
+// loader metadata only knows game versions, which a renamed vanilla id is not
+pub fn game_version(vanilla: &VersionManifest) -> String {
+    detect_game_version(vanilla).unwrap_or_else(|| vanilla.id.clone())
+}
+
+pub fn check_game_version(loader: &str, expected: &str, vanilla: &VersionManifest) -> Result<()> {
+    let actual = game_version(vanilla);
+    if actual != expected {
+        bail!(
+            "{loader} is made for minecraft {expected}, but {} is minecraft {actual}",
+            vanilla.id
+        );
+    }
+    Ok(())
+}
+
+// loader profiles name their parent by game version, a renamed vanilla lives under its own id
+pub fn with_parent(mut profile: Value, vanilla: &VersionManifest) -> Value {
+    profile["inheritsFrom"] = Value::String(vanilla.id.clone());
+    profile
+}
+
+/// Registers a loader profile in `versions/<id>/<id>.json`, keeping unknown fields as they are.
+pub async fn write_child_version(
+    minecraft_dir: &AbsPathBuf,
+    profile: Value,
+) -> Result<VersionManifest> {
+    let manifest: VersionManifest =
+        serde_json::from_value(profile.clone()).context("Invalid loader profile")?;
+    if manifest.inherits_from.is_none() {
+        return Err(anyhow!(
+            "Loader profile {} has no parent version",
+            manifest.id
+        ));
+    }
+
+    let version_dir = minecraft_dir.join(VERSIONS_DIR_NAME).join(&manifest.id);
+    fs::create_dir_all(&version_dir).await?;
+    let json_path = version_dir.join(format!("{}.json", manifest.id));
+    fs::write(&json_path, serde_json::to_vec_pretty(&profile)?).await?;
+    info!("Installed {} into {}", manifest.id, version_dir.display());
+    Ok(manifest)
+}
+
+pub fn library_path(libraries_dir: &AbsPathBuf, coordinate: &str) -> Result<AbsPathBuf> {
+    Library::from_coordinate(coordinate)
+        .get_jar_path(libraries_dir.clone())
+        .ok_or_else(|| anyhow!("Invalid maven coordinate {coordinate}"))
+}
+
+pub fn path_string(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+pub fn read_entry(installer: &mut ZipArchive<File>, name: &str) -> Result<Vec<u8>> {
+    let mut entry = installer
+        .by_name(name)
+        .with_context(|| format!("{name} not found in installer"))?;
+    let mut content = Vec::with_capacity(entry.size() as usize);
+    entry.read_to_end(&mut content)?;
+    Ok(content)
+}
+
+pub fn extract_entry(installer: &mut ZipArchive<File>, name: &str, dest: &Path) -> Result<()> {
+    let content = read_entry(installer, name)?;
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(dest, content)?;
+    Ok(())
+}
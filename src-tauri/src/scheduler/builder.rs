@@ -68,6 +68,14 @@ impl ParallelStarter {
     pub fn add<T: Task + 'static>(self, task: T) -> GroupBuilder<T, Parallel<T>> {
         GroupBuilder::new(self.name).add(task)
     }
+
+    pub fn extend<I, T>(self, iter: I) -> GroupBuilder<T, Parallel<T>>
+    where
+        I: IntoIterator<Item = T>,
+        T: Task + 'static,
+    {
+        GroupBuilder::new(self.name).extend(iter)
+    }
 }
 
 pub struct RaceStarter {
@@ -5,6 +5,7 @@ use anyhow::Result;
 use dashmap::DashMap;
 use std::sync::Arc;
 use tokio::sync::{Semaphore, watch};
+use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 
 pub struct Scheduler {
@@ -32,15 +33,26 @@ impl Scheduler {
     where
         T: Task<Input = ()>,
     {
-        let ctx = Context {
+        task.run((), self.root_context()).await
+    }
+
+    // runs the task in the background, its progress can be followed with the task id
+    pub fn spawn<T>(&self, task: T) -> JoinHandle<Result<T::Output>>
+    where
+        T: Task<Input = ()> + 'static,
+    {
+        let ctx = self.root_context();
+        tokio::spawn(async move { task.run((), ctx).await })
+    }
+
+    fn root_context(&self) -> Context {
+        Context {
             race_ctx: None,
             semaphore: self.semaphore.clone(),
             registry: self.registry.clone(),
             parent_id: None,
             cancel_token: CancellationToken::new(),
-        };
-
-        task.run((), ctx).await
+        }
     }
 
     fn spawn_limit_maintainer(&self, sem: Arc<Semaphore>, mut rx: watch::Receiver<usize>) {
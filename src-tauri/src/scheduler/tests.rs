@@ -32,6 +32,22 @@ mod execution_specs {
         assert_eq!(result.unwrap(), 42);
     }
 
+    #[tokio::test]
+    async fn spawned_task_should_be_tracked_by_its_id() {
+        let scheduler = Scheduler::new(1);
+        let task = pipeline("background")
+            .first(task("step", |_| async { Ok(42) }))
+            .build();
+        let id = task.id();
+
+        let result = scheduler.spawn(task).await.unwrap();
+
+        assert_eq!(result.unwrap(), 42);
+        let snapshot = scheduler.get_snapshot(id).unwrap();
+        assert_eq!(snapshot.name, "background");
+        assert_eq!(snapshot.state, TaskState::Finished);
+    }
+
     #[tokio::test]
     async fn task_with_ctx_should_execute_successfully_too() {
         let scheduler = Scheduler::new(1);
@@ -236,6 +252,25 @@ mod concurrency_specs {
         assert_eq!(results, vec![1, 2, 3]);
     }
 
+    #[tokio::test]
+    async fn parallel_group_should_start_from_iterator() {
+        let scheduler = Scheduler::new(4);
+        let group = parallel("iter_group")
+            .extend((1..=3).map(|i| task(format!("t{i}"), move |_| async move { Ok(i) })))
+            .build();
+        let empty_group = parallel("empty_group")
+            .extend((0..0).map(|i| task(format!("t{i}"), move |_| async move { Ok(i) })))
+            .build();
+
+        let result = scheduler.run(group).await;
+        let empty_result = scheduler.run(empty_group).await;
+
+        let mut results = result.unwrap();
+        results.sort();
+        assert_eq!(results, vec![1, 2, 3]);
+        assert!(empty_result.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn race_should_return_result_of_fastest_winner() {
         let scheduler = Scheduler::new(2);
@@ -0,0 +1,56 @@
+use crate::constants::network::{
+    FABRIC_META_URL, OFFICIAL_ASSETS_BASE_URL, OFFICIAL_META_BASE_URL, QUILT_META_URL,
+};
+use crate::game_installer::catalog::VersionCatalogClient;
+use crate::utils::global_app_handle::get_global_app_handle;
+use anyhow::Result;
+use log::warn;
+use macros::settings;
+use std::sync::Arc;
+use tauri::Manager;
+
+#[settings(name = "download", post_process = post_process, update_handler = on_update, no_default)]
+pub struct DownloadSettings {
+    pub meta_base_url: String,
+    pub assets_base_url: String,
+    pub fabric_meta_url: String,
+    pub quilt_meta_url: String,
+}
+
+impl Default for DownloadSettings {
+    fn default() -> Self {
+        Self {
+            meta_base_url: OFFICIAL_META_BASE_URL.to_string(),
+            assets_base_url: OFFICIAL_ASSETS_BASE_URL.to_string(),
+            fabric_meta_url: FABRIC_META_URL.to_string(),
+            quilt_meta_url: QUILT_META_URL.to_string(),
+        }
+    }
+}
+
+fn post_process(settings: &mut DownloadSettings) -> Result<()> {
+    if settings.meta_base_url.trim().is_empty() {
+        settings.meta_base_url = OFFICIAL_META_BASE_URL.to_string();
+        warn!("Meta base url is missing, resetting to default {OFFICIAL_META_BASE_URL}");
+    }
+    if settings.assets_base_url.trim().is_empty() {
+        settings.assets_base_url = OFFICIAL_ASSETS_BASE_URL.to_string();
+        warn!("Assets base url is missing, resetting to default {OFFICIAL_ASSETS_BASE_URL}");
+    }
+    if settings.fabric_meta_url.trim().is_empty() {
+        settings.fabric_meta_url = FABRIC_META_URL.to_string();
+        warn!("Fabric meta url is missing, resetting to default {FABRIC_META_URL}");
+    }
+    if settings.quilt_meta_url.trim().is_empty() {
+        settings.quilt_meta_url = QUILT_META_URL.to_string();
+        warn!("Quilt meta url is missing, resetting to default {QUILT_META_URL}");
+    }
+    Ok(())
+}
+
+fn on_update(neo: &DownloadSettings, _old: DownloadSettings) -> Result<()> {
+    if let Some(catalog) = get_global_app_handle().try_state::<Arc<VersionCatalogClient>>() {
+        catalog.set_base_url(neo.meta_base_url.clone());
+    }
+    Ok(())
+}
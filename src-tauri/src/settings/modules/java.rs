@@ -0,0 +1,63 @@
+use crate::constants::network::{ADOPTIUM_API_URL, LIBERICA_API_URL, ZULU_API_URL};
+use crate::java_runtime::scanner::JavaScanOptions;
+use crate::java_runtime::vendor_download::VendorEndpoints;
+use anyhow::Result;
+use log::warn;
+use macros::settings;
+use std::path::PathBuf;
+
+#[settings(name = "java", post_process = post_process, no_default)]
+pub struct JavaSettings {
+    // vendor keywords such as "temurin", most preferred first
+    pub vendor_preference: Vec<String>,
+    // java homes or executables added by hand, scanned alongside the known locations
+    pub extra_paths: Vec<String>,
+    pub adoptium_api_url: String,
+    pub zulu_api_url: String,
+    pub liberica_api_url: String,
+}
+
+impl Default for JavaSettings {
+    fn default() -> Self {
+        Self {
+            vendor_preference: Vec::new(),
+            extra_paths: Vec::new(),
+            adoptium_api_url: ADOPTIUM_API_URL.to_string(),
+            zulu_api_url: ZULU_API_URL.to_string(),
+            liberica_api_url: LIBERICA_API_URL.to_string(),
+        }
+    }
+}
+
+impl JavaSettings {
+    pub fn scan_options(&self) -> JavaScanOptions {
+        JavaScanOptions {
+            minecraft_dirs: vec![],
+            user_paths: self.extra_paths.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    pub fn vendor_endpoints(&self) -> VendorEndpoints {
+        VendorEndpoints {
+            adoptium_api_url: self.adoptium_api_url.clone(),
+            zulu_api_url: self.zulu_api_url.clone(),
+            liberica_api_url: self.liberica_api_url.clone(),
+        }
+    }
+}
+
+fn post_process(settings: &mut JavaSettings) -> Result<()> {
+    if settings.adoptium_api_url.trim().is_empty() {
+        settings.adoptium_api_url = ADOPTIUM_API_URL.to_string();
+        warn!("Adoptium api url is missing, resetting to default {ADOPTIUM_API_URL}");
+    }
+    if settings.zulu_api_url.trim().is_empty() {
+        settings.zulu_api_url = ZULU_API_URL.to_string();
+        warn!("Zulu api url is missing, resetting to default {ZULU_API_URL}");
+    }
+    if settings.liberica_api_url.trim().is_empty() {
+        settings.liberica_api_url = LIBERICA_API_URL.to_string();
+        warn!("Liberica api url is missing, resetting to default {LIBERICA_API_URL}");
+    }
+    Ok(())
+}
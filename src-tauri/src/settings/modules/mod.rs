@@ -1,2 +1,4 @@
+pub mod download;
 pub mod general;
+pub mod java;
 pub mod theme;
@@ -1,6 +1,6 @@
 use anyhow::{Context, Result};
 use std::io::{PipeReader, PipeWriter};
-use std::process::Stdio;
+use std::process::{ExitStatus, Stdio};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::{Child, Command};
 use tokio::sync::mpsc;
@@ -33,6 +33,11 @@ impl Executable {
     }
 
     pub async fn run_and_get_output(&self) -> Result<String> {
+        let (_, output) = self.run_and_get_status().await?;
+        Ok(output)
+    }
+
+    pub async fn run_and_get_status(&self) -> Result<(ExitStatus, String)> {
         let mut process = self
             .spawn()
             .context("Failed to spawn process for output capture")?;
@@ -44,13 +49,13 @@ impl Executable {
             output_buffer.push('\n');
         }
 
-        process
+        let status = process
             .child
             .wait()
             .await
             .context("Failed to wait for process exit")?;
 
-        Ok(output_buffer)
+        Ok((status, output_buffer))
     }
 
     fn create_merged_pipes(&self) -> Result<(std::fs::File, PipeWriter, PipeWriter)> {
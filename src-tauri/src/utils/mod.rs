@@ -5,3 +5,4 @@ pub mod dirs;
 pub mod executor;
 pub mod global_app_handle;
 pub mod os_info;
+pub mod test_server;